@@ -61,7 +61,12 @@ def main(argv: Optional[list[str]] = None) -> int:
     p = argparse.ArgumentParser(description="Runicorn viewer sidecar (uvicorn)")
     p.add_argument("--host", default="127.0.0.1")
     p.add_argument("--port", type=int, default=8000)
-    args = p.parse_args(argv)
+    p.add_argument("--log-level", default=os.getenv("RUNICORN_LOG_LEVEL", "info"),
+                   choices=["critical", "error", "warning", "info", "debug", "trace"], type=str.lower)
+    # The desktop app may forward extra flags (RUNICORN_BACKEND_ARGS); tolerate ones we don't know
+    args, unknown = p.parse_known_args(argv)
+    if unknown:
+        log("[runicorn-viewer] ignoring unsupported arguments:", unknown)
 
     # create_app is a factory
     app_factory = getattr(viewer, "create_app")
@@ -81,7 +86,7 @@ def main(argv: Optional[list[str]] = None) -> int:
     signal.signal(signal.SIGTERM, signal_handler)
     
     try:
-        log("[runicorn-viewer] starting uvicorn:", args.host, args.port)
+        log("[runicorn-viewer] starting uvicorn:", args.host, args.port, "log level", args.log_level)
         # Disable uvicorn's default logging config to avoid "Unable to configure formatter 'default'" under PyInstaller
         # timeout_graceful_shutdown: Give time for cleanup handlers to run
         uvicorn.run(
@@ -89,7 +94,7 @@ def main(argv: Optional[list[str]] = None) -> int:
             host=args.host, 
             port=args.port, 
             factory=True, 
-            log_level="info", 
+            log_level=args.log_level, 
             log_config=None,
             timeout_graceful_shutdown=5  # 5 seconds for graceful shutdown
         )
@@ -1,3 +1,96 @@
+use std::{
+  fs::File,
+  io,
+  path::PathBuf,
+  process::Command,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use sha2::{Digest, Sha256};
+
+fn git_hash() -> String {
+  Command::new("git")
+    .args(["rev-parse", "--short", "HEAD"])
+    .output()
+    .ok()
+    .filter(|o| o.status.success())
+    .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+    .filter(|h| !h.is_empty())
+    .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Today's UTC date as YYYY-MM-DD, honoring SOURCE_DATE_EPOCH for reproducible builds.
+fn build_date() -> String {
+  let secs = std::env::var("SOURCE_DATE_EPOCH")
+    .ok()
+    .and_then(|v| v.parse::<i64>().ok())
+    .unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0));
+  // days-from-civil inverse (Howard Hinnant's algorithm)
+  let z = secs.div_euclid(86_400) + 719_468;
+  let era = z.div_euclid(146_097);
+  let doe = z - era * 146_097;
+  let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = doy - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = yoe + era * 400 + i64::from(month <= 2);
+  format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Where `externalBin` expects the sidecar for this target.
+fn sidecar_path() -> PathBuf {
+  let target = std::env::var("TARGET").unwrap_or_default();
+  let exe = if target.contains("windows") { ".exe" } else { "" };
+  PathBuf::from(format!("../sidecar/dist/runicorn-viewer-{}{}", target, exe))
+}
+
+/// Whether the bundler will code-sign the sidecar, which rewrites it after
+/// this build script ran: `RUNICORN_SIDECAR_SIGNED=1`, or on macOS a signing
+/// identity for the bundler.
+fn sidecar_signed() -> bool {
+  let target = std::env::var("TARGET").unwrap_or_default();
+  let set = |name: &str| std::env::var(name).is_ok_and(|v| !v.trim().is_empty());
+  match std::env::var("RUNICORN_SIDECAR_SIGNED") {
+    Ok(v) => v.trim() == "1",
+    Err(_) => target.contains("apple") && set("APPLE_SIGNING_IDENTITY"),
+  }
+}
+
+/// The sidecar's expected SHA-256: `RUNICORN_SIDECAR_SHA256` if set (`""`
+/// turns the check off), else the hash of the binary being bundled, else
+/// empty when there is none (dev builds) or when signing will change it.
+fn sidecar_sha256() -> String {
+  if let Ok(pinned) = std::env::var("RUNICORN_SIDECAR_SHA256") {
+    return pinned.trim().to_ascii_lowercase();
+  }
+  if sidecar_signed() {
+    return String::new();
+  }
+  let path = sidecar_path();
+  let Ok(mut file) = File::open(&path) else { return String::new() };
+  let mut hasher = Sha256::new();
+  if let Err(e) = io::copy(&mut file, &mut hasher) {
+    println!("cargo:warning=could not hash {}: {}", path.display(), e);
+    return String::new();
+  }
+  hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
 fn main() {
+  // Baked in for the About dialog and get_app_version.
+  println!("cargo:rustc-env=RUNICORN_GIT_HASH={}", git_hash());
+  println!("cargo:rustc-env=RUNICORN_BUILD_DATE={}", build_date());
+  println!("cargo:rerun-if-changed=../../../.git/HEAD");
+  println!("cargo:rerun-if-changed=../../../.git/refs/heads");
+  println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+  // Checked before every sidecar launch.
+  println!("cargo:rustc-env=RUNICORN_SIDECAR_SHA256={}", sidecar_sha256());
+  println!("cargo:rerun-if-changed={}", sidecar_path().display());
+  println!("cargo:rerun-if-env-changed=RUNICORN_SIDECAR_SHA256");
+  // Signed builds check the sidecar's code signature instead.
+  println!("cargo:rustc-env=RUNICORN_SIDECAR_SIGNED={}", if sidecar_signed() { "1" } else { "" });
+  println!("cargo:rerun-if-env-changed=RUNICORN_SIDECAR_SIGNED");
+  println!("cargo:rerun-if-env-changed=APPLE_SIGNING_IDENTITY");
   tauri_build::build()
 }
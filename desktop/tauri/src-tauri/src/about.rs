@@ -0,0 +1,66 @@
+//! Build information and the "About Runicorn" dialog.
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::AppState;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppVersion {
+    pub version: String,
+    pub git_hash: &'static str,
+    pub build_date: &'static str,
+}
+
+pub fn app_version(app: &AppHandle) -> AppVersion {
+    AppVersion {
+        version: app.package_info().version.to_string(),
+        git_hash: env!("RUNICORN_GIT_HASH"),
+        build_date: env!("RUNICORN_BUILD_DATE"),
+    }
+}
+
+fn backend_summary(app: &AppHandle) -> String {
+    let state: tauri::State<AppState> = app.state();
+    let url = state.backend_url.lock().unwrap().clone();
+    let kind = state.child.lock().unwrap().as_ref().map(|c| c.kind());
+    match (url, kind) {
+        (Some(url), Some(kind)) => format!("{} ({})", url, kind.label()),
+        (Some(url), None) => format!("{} ({})", url, crate::BackendKind::Attached.label()),
+        (None, _) => "not running".to_string(),
+    }
+}
+
+/// Plain-text version report, also what "Copy" puts on the clipboard.
+pub fn summary(app: &AppHandle) -> String {
+    let v = app_version(app);
+    format!(
+        "Runicorn Desktop {}\nCommit: {}\nBuilt: {}\nOS: {} {} ({})\nBackend: {}",
+        v.version,
+        v.git_hash,
+        v.build_date,
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        crate::platform::os_version().as_deref().unwrap_or("unknown version"),
+        backend_summary(app)
+    )
+}
+
+/// Shows the About dialog. Blocks, so call it off the main thread.
+pub fn show(app: &AppHandle) {
+    let text = summary(app);
+    let copy = app
+        .dialog()
+        .message(&text)
+        .title(crate::i18n::t("about.title"))
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::OkCancelCustom(crate::i18n::t("about.copy").into(), crate::i18n::t("about.close").into()))
+        .blocking_show();
+    if copy {
+        if let Err(e) = app.clipboard().write_text(text) {
+            log::warn!("failed to copy version info: {}", e);
+        }
+    }
+}
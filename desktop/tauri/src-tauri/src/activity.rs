@@ -0,0 +1,103 @@
+//! How many runs are active right now, polled from the backend's run list for
+//! the tray (tooltip, plus a badge where the OS has one) and
+//! `get_active_run_count`. Polls less often while the window is hidden.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{i18n, AppState};
+
+pub const DEFAULT_POLL_SECS: u64 = 5;
+// Hidden windows poll this many times less often, but at least once a minute.
+const HIDDEN_BACKOFF: u32 = 6;
+const MAX_HIDDEN_INTERVAL: Duration = Duration::from_secs(60);
+// `{ count }` whenever the number of active runs changes; `null` while unknown.
+const EVENT_ACTIVE_RUNS: &str = "runs://active";
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+// The last count, `None` while the backend can't be asked.
+static COUNT: Mutex<Option<usize>> = Mutex::new(None);
+
+/// The active run count from the last poll; `None` while no backend answers.
+pub fn count() -> Option<usize> {
+    *COUNT.lock().unwrap()
+}
+
+/// Starts the poller; later calls are no-ops.
+pub fn spawn(app: &AppHandle) {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    thread::spawn(move || run(app));
+}
+
+fn fetch(app: &AppHandle) -> Option<usize> {
+    let state: tauri::State<AppState> = app.state();
+    if state.shutting_down.load(Ordering::SeqCst) {
+        return None;
+    }
+    let base = state.backend_url.lock().unwrap().clone()?;
+    match crate::active_run_count(&base) {
+        Ok(count) => Some(count),
+        Err(e) => {
+            log::debug!("{}", e);
+            None
+        }
+    }
+}
+
+/// Whether nobody is looking: the main window is missing, hidden or minimized.
+fn window_hidden(app: &AppHandle) -> bool {
+    let Some(window) = app.get_webview_window(crate::MAIN_WINDOW) else { return true };
+    !window.is_visible().unwrap_or(true) || window.is_minimized().unwrap_or(false)
+}
+
+fn run(app: AppHandle) {
+    let interval = Duration::from_secs(app.state::<AppState>().config.run_poll_interval());
+    loop {
+        let current = fetch(&app);
+        let changed = {
+            let mut last = COUNT.lock().unwrap();
+            std::mem::replace(&mut *last, current) != current
+        };
+        if changed {
+            show(&app, current);
+        }
+        let wait = if window_hidden(&app) { (interval * HIDDEN_BACKOFF).min(MAX_HIDDEN_INTERVAL).max(interval) } else { interval };
+        thread::sleep(wait);
+    }
+}
+
+/// Puts `count` on the tray tooltip and badge and tells the frontend.
+fn show(app: &AppHandle, count: Option<usize>) {
+    let active = count.unwrap_or(0);
+    let tooltip = match active {
+        0 => i18n::t("tray.tooltip").to_string(),
+        1 => i18n::t("tray.tooltip_one").to_string(),
+        n => i18n::tf("tray.tooltip_many", &[&n]),
+    };
+    if let Some(tray) = app.tray_by_id(crate::tray::TRAY_ID) {
+        if let Err(e) = tray.set_tooltip(Some(&tooltip)) {
+            log::debug!("failed to update the tray tooltip: {}", e);
+        }
+        // Text next to the icon on macOS and Linux; Windows only has the tooltip.
+        let _ = tray.set_title((active > 0).then(|| active.to_string()));
+    }
+    #[cfg(not(windows))]
+    if let Some(window) = app.get_webview_window(crate::MAIN_WINDOW) {
+        // The dock (macOS) or launcher (Linux) badge.
+        let _ = window.set_badge_count((active > 0).then_some(active as i64));
+    }
+    if let Err(e) = app.emit(EVENT_ACTIVE_RUNS, serde_json::json!({ "count": count })) {
+        log::warn!("failed to emit {}: {}", EVENT_ACTIVE_RUNS, e);
+    }
+}
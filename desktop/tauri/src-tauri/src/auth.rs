@@ -0,0 +1,98 @@
+//! Per-launch secret shared with the backends we spawn, so other local
+//! processes can't talk to them. The backend checks it in its
+//! `AuthTokenMiddleware`; the webview gets it once in the URL and then as a cookie.
+
+use std::{
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use serde::de::DeserializeOwned;
+
+pub const ENV_KEY: &str = "RUNICORN_AUTH_TOKEN";
+pub const HEADER: &str = "X-Runicorn-Token";
+pub const QUERY_PARAM: &str = "runicorn_token";
+
+static TOKEN: OnceLock<String> = OnceLock::new();
+// `Some` while connected to a remote backend, holding its own token if it has one.
+static REMOTE_TOKEN: Mutex<Option<Option<String>>> = Mutex::new(None);
+
+/// The token for this launch; stable across backend restarts so the webview
+/// cookie stays valid.
+pub fn token() -> &'static str {
+    TOKEN.get_or_init(|| {
+        let mut bytes = [0u8; 32];
+        if let Err(e) = getrandom::getrandom(&mut bytes) {
+            // Never worth failing startup over; fall back to std's randomly seeded hasher.
+            log::warn!("OS random source unavailable ({}), using a weaker token", e);
+            fill_fallback(&mut bytes);
+        }
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    })
+}
+
+/// Switches to a remote backend, sending its own `token` (from its profile)
+/// if it has one. Our per-launch token never goes to a remote.
+pub fn use_remote(token: Option<String>) {
+    *REMOTE_TOKEN.lock().unwrap() = Some(token);
+}
+
+/// Back to a local backend, which gets our per-launch token.
+pub fn use_local() {
+    *REMOTE_TOKEN.lock().unwrap() = None;
+}
+
+/// The token the current backend expects; `None` for a remote without one.
+pub fn current() -> Option<String> {
+    match REMOTE_TOKEN.lock().unwrap().clone() {
+        Some(remote) => remote,
+        None => Some(token().to_string()),
+    }
+}
+
+fn fill_fallback(bytes: &mut [u8]) {
+    use std::hash::{BuildHasher, Hasher};
+    let state = std::collections::hash_map::RandomState::new();
+    for (i, chunk) in bytes.chunks_mut(8).enumerate() {
+        let mut hasher = state.build_hasher();
+        hasher.write_usize(i);
+        hasher.write_u32(std::process::id());
+        hasher.write_u128(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0));
+        chunk.copy_from_slice(&hasher.finish().to_le_bytes()[..chunk.len()]);
+    }
+}
+
+/// A GET request carrying the token header.
+pub fn get(url: &str) -> ureq::Request {
+    request("GET", url)
+}
+
+/// A request with any method carrying the token header (if the backend has
+/// one), through the proxy
+/// if `url` is off the machine.
+pub fn request(method: &str, url: &str) -> ureq::Request {
+    let request = crate::proxy::agent(url).request(method, url);
+    match current() {
+        Some(token) => request.set(HEADER, &token),
+        None => request,
+    }
+}
+
+// Short enough that an unresponsive backend barely delays quitting.
+const RUNS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The run list of the backend at `base` (`/api/runs`), shared by the pollers
+/// and the quit check; the error says whether it couldn't be fetched or read.
+pub fn runs<T: DeserializeOwned>(base: &str) -> Result<Vec<T>, String> {
+    let url = format!("{}/api/runs", base.trim_end_matches('/'));
+    let reply = get(&url).timeout(RUNS_TIMEOUT).call().map_err(|e| format!("could not fetch the run list: {}", e))?;
+    reply.into_json().map_err(|e| format!("could not read the run list: {}", e))
+}
+
+/// Adds the token as a query parameter; the backend answers with a cookie
+/// that covers every later request from the page.
+pub fn add_to_url(url: &mut tauri::Url) {
+    if let Some(token) = current() {
+        url.query_pairs_mut().append_pair(QUERY_PARAM, &token);
+    }
+}
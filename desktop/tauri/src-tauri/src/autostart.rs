@@ -0,0 +1,47 @@
+//! Launch at login through tauri-plugin-autostart. Off until the user turns
+//! it on from the tray or `set_autostart`; the login entry passes
+//! [`HIDDEN_ARG`] so the app starts in the tray, unless `start_hidden = false`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::AppState;
+
+pub const HIDDEN_ARG: &str = "--hidden";
+
+// The first main window is the only one a hidden launch keeps hidden.
+static FIRST_WINDOW_SHOWN: AtomicBool = AtomicBool::new(false);
+
+/// Whether this launch came from the login entry and should stay in the tray.
+/// Never without a tray, which would leave no way to open the window.
+pub fn start_hidden(app: &AppHandle) -> bool {
+    std::env::args().skip(1).any(|arg| arg == HIDDEN_ARG)
+        && app.state::<AppState>().config.start_hidden()
+        && app.tray_by_id(crate::tray::TRAY_ID).is_some()
+}
+
+/// [`start_hidden`] for the first main window only; later ones always show.
+pub fn hide_first_window(app: &AppHandle) -> bool {
+    !FIRST_WINDOW_SHOWN.swap(true, Ordering::SeqCst) && start_hidden(app)
+}
+
+pub fn is_enabled(app: &AppHandle) -> bool {
+    app.autolaunch().is_enabled().unwrap_or_else(|e| {
+        log::warn!("failed to read the launch-at-login setting: {}", e);
+        false
+    })
+}
+
+/// Adds or removes the login entry; a no-op when it is already that way.
+pub fn set(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    if is_enabled(app) == enabled {
+        return Ok(());
+    }
+    let launcher = app.autolaunch();
+    let result = if enabled { launcher.enable() } else { launcher.disable() };
+    result.map_err(|e| format!("Failed to change the launch-at-login setting: {}", e))?;
+    log::info!("launch at login {}", if enabled { "enabled" } else { "disabled" });
+    Ok(())
+}
@@ -0,0 +1,247 @@
+//! Captures the backend's stdout/stderr into `<app log dir>/backend.log`.
+//!
+//! The file rolls over once it exceeds [`MAX_LOG_BYTES`]: it is gzipped to
+//! `backend.log.1.gz`, older ones shift to `.2.gz` and so on, and only the
+//! newest `log_retention` of those (3 by default) are kept, within
+//! [`MAX_TOTAL_BYTES`] overall. Lines can also be streamed live to the
+//! frontend as `backend://log` events.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_shell::process::CommandEvent;
+
+use crate::AppState;
+
+pub const LOG_FILE_NAME: &str = "backend.log";
+// Uncompressed roll-over file from before rotated logs were gzipped.
+const LEGACY_ROLLED_FILE_NAME: &str = "backend.log.1";
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+/// Cap on the current log plus the rotated ones.
+const MAX_TOTAL_BYTES: u64 = 20 * 1024 * 1024;
+pub const DEFAULT_RETENTION: usize = 3;
+/// Upper bound for [`tail`], whatever the caller asks for.
+pub const MAX_TAIL_LINES: usize = 5000;
+pub const EVENT_LOG_LINE: &str = "backend://log";
+
+// Live streaming is opt-in; most of the time nobody is watching the log view.
+static STREAMING: AtomicBool = AtomicBool::new(false);
+
+pub fn set_streaming(enabled: bool) {
+    STREAMING.store(enabled, Ordering::Relaxed);
+}
+
+/// Shared handle to the backend log; clones write to the same file.
+#[derive(Clone)]
+pub struct BackendLog {
+    inner: Arc<Mutex<LogFile>>,
+    app: AppHandle,
+}
+
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    retention: usize,
+}
+
+/// `backend.log.<n>.gz`; 1 is the newest.
+fn rotated_path(dir: &Path, n: usize) -> PathBuf {
+    dir.join(format!("{}.{}.gz", LOG_FILE_NAME, n))
+}
+
+fn file_len(path: &Path) -> u64 {
+    fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+fn compress(from: &Path, to: &Path) -> io::Result<()> {
+    let mut encoder = GzEncoder::new(File::create(to)?, Compression::default());
+    io::copy(&mut File::open(from)?, &mut encoder)?;
+    encoder.finish()?;
+    fs::remove_file(from)
+}
+
+/// Deletes rotated logs beyond `retention`, then the oldest remaining ones
+/// until everything fits in [`MAX_TOTAL_BYTES`].
+fn prune(dir: &Path, retention: usize) {
+    let _ = fs::remove_file(dir.join(LEGACY_ROLLED_FILE_NAME));
+    let mut n = retention + 1;
+    while rotated_path(dir, n).exists() {
+        let _ = fs::remove_file(rotated_path(dir, n));
+        n += 1;
+    }
+    let mut kept: Vec<PathBuf> = (1..=retention).map(|n| rotated_path(dir, n)).filter(|p| p.exists()).collect();
+    let mut total = file_len(&dir.join(LOG_FILE_NAME)) + kept.iter().map(|p| file_len(p)).sum::<u64>();
+    while total > MAX_TOTAL_BYTES {
+        let Some(oldest) = kept.pop() else { break };
+        total -= file_len(&oldest);
+        let _ = fs::remove_file(&oldest);
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<(File, u64)> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    Ok((file, len))
+}
+
+impl LogFile {
+    fn rotate(&mut self) -> io::Result<()> {
+        let dir = self.path.parent().map(Path::to_path_buf).unwrap_or_default();
+        if self.retention == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            for n in (1..self.retention).rev() {
+                let from = rotated_path(&dir, n);
+                if from.exists() {
+                    fs::rename(&from, rotated_path(&dir, n + 1))?;
+                }
+            }
+            // Renamed first so the backend keeps writing to a fresh file if compressing fails.
+            let rolled = dir.join(LEGACY_ROLLED_FILE_NAME);
+            fs::rename(&self.path, &rolled)?;
+            if let Err(e) = compress(&rolled, &rotated_path(&dir, 1)) {
+                log::warn!("failed to compress the rotated backend log: {}", e);
+            }
+        }
+        prune(&dir, self.retention);
+        let (file, written) = open_append(&self.path)?;
+        self.file = file;
+        self.written = written;
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let len = line.len() as u64 + 1;
+        if self.written > 0 && self.written + len > MAX_LOG_BYTES {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.written += len;
+        Ok(())
+    }
+}
+
+/// Directory holding the backend log files.
+pub fn log_dir(app: &AppHandle) -> io::Result<PathBuf> {
+    app.path()
+        .app_log_dir()
+        .map_err(|e| io::Error::new(io::ErrorKind::NotFound, e.to_string()))
+}
+
+impl BackendLog {
+    pub fn create(app: &AppHandle) -> io::Result<Self> {
+        let dir = log_dir(app)?;
+        fs::create_dir_all(&dir)?;
+        let retention = app.state::<AppState>().config.log_retention.unwrap_or(DEFAULT_RETENTION);
+        prune(&dir, retention);
+        let path = dir.join(LOG_FILE_NAME);
+        let (file, written) = open_append(&path)?;
+        Ok(BackendLog {
+            inner: Arc::new(Mutex::new(LogFile { path, file, written, retention })),
+            app: app.clone(),
+        })
+    }
+
+    /// Appends one line, stripping any trailing newline from the backend output.
+    pub fn write(&self, bytes: &[u8]) {
+        let text = String::from_utf8_lossy(bytes);
+        let line = text.trim_end_matches(['\r', '\n']);
+        if let Ok(mut f) = self.inner.lock() {
+            let _ = f.write_line(line);
+        }
+        if STREAMING.load(Ordering::Relaxed) {
+            let _ = self.app.emit(EVENT_LOG_LINE, line);
+        }
+    }
+
+    /// Writes a marker line from the desktop shell itself.
+    pub fn note(&self, message: &str) {
+        self.write(format!("[runicorn-desktop] {}", message).as_bytes());
+    }
+
+    /// Drains a child pipe on its own thread so the child never blocks on a full buffer.
+    pub fn drain<R: Read + Send + 'static>(&self, reader: R) {
+        let log = self.clone();
+        thread::spawn(move || {
+            let mut reader = BufReader::new(reader);
+            let mut buf = Vec::new();
+            loop {
+                buf.clear();
+                match reader.read_until(b'\n', &mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => log.write(&buf),
+                }
+            }
+        });
+    }
+
+    /// Records one event from the sidecar's channel (stdout, stderr and exit status).
+    pub fn sidecar_event(&self, event: &CommandEvent) {
+        match event {
+            CommandEvent::Stdout(bytes) | CommandEvent::Stderr(bytes) => self.write(bytes),
+            CommandEvent::Error(e) => self.note(&format!("sidecar error: {}", e)),
+            CommandEvent::Terminated(t) => self.note(&format!("sidecar exited with code {:?}", t.code)),
+            _ => {}
+        }
+    }
+}
+
+fn read_lines(path: &Path) -> io::Result<Vec<String>> {
+    let read = match File::open(path) {
+        Ok(file) if path.extension().is_some_and(|ext| ext == "gz") => {
+            let mut bytes = Vec::new();
+            GzDecoder::new(file).read_to_end(&mut bytes).map(|_| bytes)
+        }
+        Ok(mut file) => {
+            let mut bytes = Vec::new();
+            file.read_to_end(&mut bytes).map(|_| bytes)
+        }
+        Err(e) => Err(e),
+    };
+    match read {
+        Ok(bytes) => Ok(String::from_utf8_lossy(&bytes).lines().map(str::to_string).collect()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// The last `lines` lines of backend output (capped at [`MAX_TAIL_LINES`]),
+/// reaching into the newest rotated file when the current one is short.
+/// Empty if no backend has logged anything yet.
+pub fn tail(app: &AppHandle, lines: usize) -> io::Result<String> {
+    let n = lines.min(MAX_TAIL_LINES);
+    let dir = log_dir(app)?;
+    let mut out = read_lines(&dir.join(LOG_FILE_NAME))?;
+    if out.len() < n {
+        let mut older = read_lines(&rotated_path(&dir, 1))?;
+        older.append(&mut out);
+        out = older;
+    }
+    let start = out.len().saturating_sub(n);
+    Ok(out[start..].join("\n"))
+}
+
+/// Sizes of the backend log files, for diagnostics.
+pub struct Usage {
+    pub current_bytes: u64,
+    pub rotated_files: usize,
+    pub total_bytes: u64,
+}
+
+pub fn usage(app: &AppHandle) -> io::Result<Usage> {
+    let dir = log_dir(app)?;
+    let current_bytes = file_len(&dir.join(LOG_FILE_NAME));
+    let rotated: Vec<u64> = (1..).map(|n| rotated_path(&dir, n)).take_while(|p| p.exists()).map(|p| file_len(&p)).collect();
+    Ok(Usage { current_bytes, rotated_files: rotated.len(), total_bytes: current_bytes + rotated.iter().sum::<u64>() })
+}
@@ -0,0 +1,30 @@
+//! IPC access for pages that aren't bundled with the app. The static `main`
+//! capability (capabilities/main.json) covers only local content; the local
+//! backend's pages (and the Vite dev server) get event access granted here
+//! for their exact origin once it is known, so other servers on localhost
+//! that the user happens to open get no IPC at all.
+
+use std::{collections::HashSet, sync::Mutex};
+
+use tauri::{ipc::CapabilityBuilder, AppHandle, Manager, Url};
+
+// Origins already granted; capabilities can't be removed, only added once.
+static GRANTED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// Lets the app windows on `url`'s origin use events and the app's commands.
+pub fn allow(app: &AppHandle, url: &str) {
+    let Ok(parsed) = url.parse::<Url>() else { return };
+    let origin = parsed.origin().ascii_serialization();
+    if !GRANTED.lock().unwrap().get_or_insert_with(HashSet::new).insert(origin.clone()) {
+        return;
+    }
+    let capability = CapabilityBuilder::new(format!("backend-{}", origin))
+        .remote(format!("{}/*", origin))
+        .window(crate::MAIN_WINDOW)
+        .window("compare-*")
+        .permission("core:event:default");
+    match app.add_capability(capability) {
+        Ok(()) => log::debug!("granted event access to pages on {}", origin),
+        Err(e) => log::warn!("failed to grant event access to {}: {}", origin, e),
+    }
+}
@@ -0,0 +1,133 @@
+//! Optional desktop settings from `<app config dir>/runicorn.toml`.
+//!
+//! Every field is optional; the matching environment variables (and `--port`)
+//! still take precedence over the file.
+
+use std::{fs, path::PathBuf};
+
+use serde::Deserialize;
+use tauri::{AppHandle, Manager};
+
+pub const CONFIG_FILE_NAME: &str = "runicorn.toml";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Pinned backend port, like `RUNICORN_PORT`.
+    pub port: Option<u16>,
+    /// Storage folder for the backend, like `RUNICORN_DIR`.
+    pub storage_dir: Option<PathBuf>,
+    /// Readiness timeout in seconds, like `RUNICORN_STARTUP_TIMEOUT`.
+    pub startup_timeout: Option<u64>,
+    /// Health check path, like `RUNICORN_HEALTH_PATH`.
+    pub health_path: Option<String>,
+    /// Extra backend flags, like `RUNICORN_BACKEND_ARGS`.
+    pub backend_args: Vec<String>,
+    /// Backend verbosity (`debug`, `info`, `warning` or `error`), like `RUNICORN_LOG_LEVEL`.
+    pub log_level: Option<String>,
+    /// Rotated backend logs to keep besides the current one.
+    pub log_retention: Option<usize>,
+    /// Set to `false` to skip the bundled sidecar and start the Python backend directly.
+    pub prefer_sidecar: Option<bool>,
+    /// Set to `true` to check for updates at startup; off until releases are signed.
+    pub check_for_updates: Option<bool>,
+    /// Content-Security-Policy for backend pages, like `RUNICORN_CSP`; `""` turns it off.
+    pub csp: Option<String>,
+    /// URL prefixes besides the local backend that may open inside the app window.
+    pub allowed_urls: Vec<String>,
+    /// Set to `false` to quit without asking while a run is active.
+    pub confirm_quit: Option<bool>,
+    /// Set to `false` to stop the backend without asking while a run is active.
+    pub confirm_stop: Option<bool>,
+    /// Set to `true` for a translucent macOS-style main window; ignored elsewhere.
+    pub vibrancy: Option<bool>,
+    /// Shortcut that shows or hides the main window from anywhere; `""` turns it off.
+    pub global_shortcut: Option<String>,
+    /// Set to `false` to turn off notifications when a run finishes.
+    pub notify_runs: Option<bool>,
+    /// Set to `true` to also notify for runs started before the app was opened.
+    pub notify_all_runs: Option<bool>,
+    /// Seconds between active-run checks for the tray badge; longer while the window is hidden.
+    pub run_poll_interval: Option<u64>,
+    /// Set to `false` to show the window when launched at login, instead of only the tray icon.
+    pub start_hidden: Option<bool>,
+    /// Proxy for the updater and remote backends, instead of `HTTPS_PROXY`/`HTTP_PROXY`; `""` turns it off.
+    pub proxy: Option<String>,
+    /// Hosts that skip the proxy, on top of `NO_PROXY`.
+    pub no_proxy: Vec<String>,
+    /// Set to `false` to never start a backend and only connect to one, like `RUNICORN_NO_SPAWN=1`.
+    pub spawn_backend: Option<bool>,
+    /// Backend to connect to when `spawn_backend = false`, like `RUNICORN_BACKEND_URL`.
+    pub backend_url: Option<String>,
+}
+
+impl Config {
+    pub fn prefer_sidecar(&self) -> bool {
+        self.prefer_sidecar.unwrap_or(true)
+    }
+
+    pub fn check_for_updates(&self) -> bool {
+        self.check_for_updates.unwrap_or(false)
+    }
+
+    pub fn confirm_quit(&self) -> bool {
+        self.confirm_quit.unwrap_or(true)
+    }
+
+    pub fn confirm_stop(&self) -> bool {
+        self.confirm_stop.unwrap_or(true)
+    }
+
+    pub fn notify_runs(&self) -> bool {
+        self.notify_runs.unwrap_or(true)
+    }
+
+    pub fn notify_all_runs(&self) -> bool {
+        self.notify_all_runs.unwrap_or(false)
+    }
+
+    pub fn run_poll_interval(&self) -> u64 {
+        self.run_poll_interval.unwrap_or(crate::activity::DEFAULT_POLL_SECS).max(1)
+    }
+
+    pub fn start_hidden(&self) -> bool {
+        self.start_hidden.unwrap_or(true)
+    }
+
+    pub fn spawn_backend(&self) -> bool {
+        self.spawn_backend.unwrap_or(true)
+    }
+
+    pub fn vibrancy(&self) -> bool {
+        self.vibrancy.unwrap_or(false)
+    }
+
+    pub fn global_shortcut(&self) -> Option<&str> {
+        let combo = self.global_shortcut.as_deref().unwrap_or(crate::hotkey::DEFAULT_SHORTCUT).trim();
+        (!combo.is_empty()).then_some(combo)
+    }
+}
+
+/// Reads the config file, falling back to defaults when it is missing or invalid.
+pub fn load(app: &AppHandle) -> Config {
+    let Ok(dir) = app.path().app_config_dir() else { return Config::default() };
+    let path = dir.join(CONFIG_FILE_NAME);
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Config::default(),
+        Err(e) => {
+            log::warn!("failed to read {}: {}; using defaults", path.display(), e);
+            return Config::default();
+        }
+    };
+    match toml::from_str::<Config>(&text) {
+        Ok(config) => {
+            log::info!("loaded settings from {}", path.display());
+            config
+        }
+        Err(e) => {
+            log::warn!("ignoring malformed {}: {}", path.display(), e);
+            Config::default()
+        }
+    }
+}
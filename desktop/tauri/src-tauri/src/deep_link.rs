@@ -0,0 +1,81 @@
+//! `runicorn://run/<run_id>` links that open a run in the main window.
+//!
+//! A link that arrives before the backend is ready (e.g. the one that launched
+//! the app) is queued and applied once startup finishes.
+
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::AppState;
+
+pub const SCHEME: &str = "runicorn";
+
+static PENDING: Mutex<Option<String>> = Mutex::new(None);
+
+/// The run id from a `runicorn://run/...` URL. Leading segments such as a
+/// project or experiment name (`runicorn://run/resnet50/run_0042`) are allowed
+/// for readability; the last segment is the run id.
+fn run_id(url: &Url) -> Option<String> {
+    if url.scheme() != SCHEME || url.host_str() != Some("run") {
+        return None;
+    }
+    url.path_segments()?.rfind(|s| !s.is_empty()).map(str::to_string)
+}
+
+fn navigate(app: &AppHandle, run_id: &str) -> bool {
+    let state: tauri::State<AppState> = app.state();
+    let Some(base) = state.backend_url.lock().unwrap().clone() else { return false };
+    let Some(window) = app.get_webview_window(crate::MAIN_WINDOW) else { return false };
+    match crate::window_url(app, &base, Some(run_id)) {
+        Ok(url) => {
+            if let Err(e) = window.navigate(url) {
+                log::error!("failed to open run {}: {}", run_id, e);
+            }
+            let _ = window.set_focus();
+        }
+        Err(e) => log::error!("{}", e),
+    }
+    true
+}
+
+/// Opens the run named by the last recognized link, or queues it until the
+/// backend is ready.
+pub fn handle(app: &AppHandle, urls: Vec<Url>) {
+    let Some(id) = urls.iter().rev().find_map(run_id) else {
+        if !urls.is_empty() {
+            log::warn!("ignoring unrecognized link(s): {:?}", urls);
+        }
+        return;
+    };
+    log::info!("deep link to run {}", id);
+    if !navigate(app, &id) {
+        *PENDING.lock().unwrap() = Some(id);
+    }
+}
+
+/// Applies a queued link; called once the main window points at a ready backend.
+pub fn flush(app: &AppHandle) {
+    let pending = PENDING.lock().unwrap().take();
+    if let Some(id) = pending {
+        if !navigate(app, &id) {
+            *PENDING.lock().unwrap() = Some(id);
+        }
+    }
+}
+
+/// Hooks up link delivery; call from `setup`.
+pub fn init(app: &AppHandle) {
+    // Installers register the scheme; dev builds and Linux AppImages have to do it at runtime.
+    #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+    if let Err(e) = app.deep_link().register_all() {
+        log::warn!("failed to register the {}:// scheme: {}", SCHEME, e);
+    }
+    let app_handle = app.clone();
+    app.deep_link().on_open_url(move |event| handle(&app_handle, event.urls()));
+    // The link that launched the app, if any.
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        handle(app, urls);
+    }
+}
@@ -0,0 +1,89 @@
+//! One plain-text report with everything support usually asks for.
+
+use std::fmt::Write;
+
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+const LOG_LINES: usize = 50;
+
+fn path_or_reason(result: Result<std::path::PathBuf, String>) -> String {
+    match result {
+        Ok(path) => path.display().to_string(),
+        Err(reason) => format!("not found ({})", reason),
+    }
+}
+
+fn found(path: Option<std::path::PathBuf>) -> String {
+    path.map(|p| p.display().to_string()).unwrap_or_else(|| "not found".to_string())
+}
+
+/// Builds the report. Probes the backend and Python, so call it off the main thread.
+pub fn collect(app: &AppHandle) -> String {
+    let state: tauri::State<AppState> = app.state();
+    let status = crate::backend_status(&state);
+    let port = status
+        .url
+        .as_deref()
+        .and_then(|u| u.parse::<tauri::Url>().ok())
+        .and_then(|u| u.port_or_known_default())
+        .map(|p| p.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let python = match crate::python::find() {
+        Ok((cmd, (major, minor))) => format!("{} ({}.{})", cmd, major, minor),
+        Err(missing) => format!("not usable (tried {})", missing.tried.join(", ")),
+    };
+
+    // Writing to a String can't fail.
+    let mut out = String::new();
+    let _ = writeln!(out, "{}", crate::about::summary(app));
+    let _ = writeln!(out);
+    let _ = writeln!(out, "Backend URL: {}", status.url.as_deref().unwrap_or("-"));
+    let _ = writeln!(out, "Port: {}", port);
+    let _ = writeln!(out, "Kind: {}", status.kind.map(|k| k.label()).unwrap_or("-"));
+    let _ = writeln!(out, "PID: {}", status.pid.map(|p| p.to_string()).unwrap_or_else(|| "-".to_string()));
+    let _ = writeln!(out, "Health ({}): {}", crate::health_path(), if status.healthy { "ok" } else { "not responding" });
+    let _ = writeln!(out, "Startup: {}", crate::timings::summary());
+    let _ = writeln!(out);
+    let _ = writeln!(out, "Sidecar: {}", path_or_reason(crate::resolve_sidecar()));
+    let _ = writeln!(out, "Sidecar check: {}", crate::integrity::method());
+    if let Some(dev) = crate::frontend::dev_server() {
+        let _ = writeln!(out, "Frontend: Vite dev server at {}", dev);
+    } else if crate::frontend::embedded(app) {
+        let _ = writeln!(out, "Frontend: embedded");
+    } else {
+        let _ = writeln!(out, "Frontend dist: {}", found(crate::frontend_dist(app)));
+    }
+    let _ = writeln!(out, "Python src dir: {}", found(crate::python_src_dir(app)));
+    let _ = writeln!(out, "Python: {}", python);
+    let _ = writeln!(out, "Storage folder: {}", found(crate::effective_storage_dir(app)));
+    match crate::backend_log::usage(app) {
+        Ok(usage) => {
+            let _ = writeln!(
+                out,
+                "Backend log: {} KB, plus {} rotated ({} KB in all)",
+                usage.current_bytes / 1024,
+                usage.rotated_files,
+                usage.total_bytes / 1024
+            );
+        }
+        Err(e) => {
+            let _ = writeln!(out, "Backend log: unavailable ({})", e);
+        }
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "Last {} backend log lines:", LOG_LINES);
+    match crate::backend_log::tail(app, LOG_LINES) {
+        Ok(lines) if lines.is_empty() => {
+            let _ = writeln!(out, "(empty)");
+        }
+        Ok(lines) => {
+            let _ = writeln!(out, "{}", lines);
+        }
+        Err(e) => {
+            let _ = writeln!(out, "(unreadable: {})", e);
+        }
+    }
+    out
+}
@@ -0,0 +1,147 @@
+//! `export_view`: saves what the main window shows as a PDF (the webview's
+//! print to PDF) or a PNG (its preview capture), for putting dashboards into
+//! reports. Only WebView2 does either without a dialog, so other platforms
+//! get an error saying so.
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use tauri::WebviewWindow;
+
+// Printing a long page of charts takes a while.
+#[cfg_attr(not(windows), allow(dead_code))]
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy)]
+pub enum Format {
+    Pdf,
+    Png,
+}
+
+impl Format {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "pdf" => Ok(Format::Pdf),
+            "png" => Ok(Format::Png),
+            _ => Err(format!("Unsupported export format {:?}; use \"pdf\" or \"png\".", raw)),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Format::Pdf => "PDF",
+            Format::Png => "PNG",
+        }
+    }
+}
+
+/// Checks that `raw` is an absolute path in an existing folder.
+pub fn validate(raw: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(raw.trim());
+    if !path.is_absolute() {
+        return Err(format!("{} is not an absolute path.", raw));
+    }
+    match path.parent() {
+        Some(dir) if dir.is_dir() => Ok(path),
+        _ => Err(format!("The folder for {} does not exist.", path.display())),
+    }
+}
+
+/// Renders `window`'s page to `path`, blocking until the file is written.
+/// Call it off the main thread, which does the rendering.
+pub fn export(window: &WebviewWindow, format: Format, path: &Path) -> Result<(), String> {
+    render(window, format, path)?;
+    log::info!("exported window {} as {} to {}", window.label(), format.label(), path.display());
+    Ok(())
+}
+
+#[cfg(windows)]
+fn render(window: &WebviewWindow, format: Format, path: &Path) -> Result<(), String> {
+    use std::sync::mpsc;
+
+    use windows::core::HSTRING;
+
+    let (tx, rx) = mpsc::channel::<Result<(), String>>();
+    let target = HSTRING::from(path.as_os_str());
+    window
+        .with_webview(move |webview| {
+            // SAFETY: runs on the main thread, which owns the WebView2 controller.
+            if let Err(e) = unsafe { webview2::start(webview, format, &target, tx.clone()) } {
+                let _ = tx.send(Err(e.to_string()));
+            }
+        })
+        .map_err(|e| e.to_string())?;
+    match rx.recv_timeout(EXPORT_TIMEOUT) {
+        Ok(result) => result.map_err(|e| format!("Exporting the {} failed: {}", format.label(), e)),
+        Err(_) => Err(format!("Exporting the {} did not finish within {} seconds.", format.label(), EXPORT_TIMEOUT.as_secs())),
+    }
+}
+
+#[cfg(windows)]
+mod webview2 {
+    use std::sync::mpsc::Sender;
+
+    use tauri::webview::PlatformWebview;
+    use webview2_com::{
+        CapturePreviewCompletedHandler,
+        Microsoft::Web::WebView2::Win32::{ICoreWebView2_7, COREWEBVIEW2_CAPTURE_PREVIEW_IMAGE_FORMAT_PNG},
+        PrintToPdfCompletedHandler,
+    };
+    use windows::{
+        core::{Interface, HSTRING},
+        Win32::{
+            Storage::FileSystem::FILE_ATTRIBUTE_NORMAL,
+            System::Com::{STGM_CREATE, STGM_WRITE},
+            UI::Shell::SHCreateStreamOnFileEx,
+        },
+    };
+
+    use super::Format;
+
+    /// Starts the export; `done` gets the outcome once WebView2 finishes.
+    pub unsafe fn start(
+        webview: PlatformWebview,
+        format: Format,
+        path: &HSTRING,
+        done: Sender<Result<(), String>>,
+    ) -> windows::core::Result<()> {
+        let core = webview.controller().CoreWebView2()?;
+        match format {
+            Format::Pdf => {
+                let core: ICoreWebView2_7 = core.cast()?;
+                let handler = PrintToPdfCompletedHandler::create(Box::new(move |result, written| {
+                    let outcome = match result {
+                        Err(e) => Err(e.to_string()),
+                        Ok(()) if !written => Err("the webview could not write the file".to_string()),
+                        Ok(()) => Ok(()),
+                    };
+                    let _ = done.send(outcome);
+                    Ok(())
+                }));
+                core.PrintToPdf(path, None, &handler)
+            }
+            Format::Png => {
+                let stream = SHCreateStreamOnFileEx(path, (STGM_CREATE | STGM_WRITE).0, FILE_ATTRIBUTE_NORMAL.0, true, None)?;
+                // Released in the handler, which closes the file before we report success.
+                let held = stream.clone();
+                let handler = CapturePreviewCompletedHandler::create(Box::new(move |result| {
+                    drop(held);
+                    let _ = done.send(result.map_err(|e| e.to_string()));
+                    Ok(())
+                }));
+                core.CapturePreview(COREWEBVIEW2_CAPTURE_PREVIEW_IMAGE_FORMAT_PNG, &stream, &handler)
+            }
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn render(_window: &WebviewWindow, format: Format, _path: &Path) -> Result<(), String> {
+    Err(format!(
+        "Exporting the view as {} is not supported on {} yet; use the system print dialog or a screenshot instead.",
+        format.label(),
+        std::env::consts::OS
+    ))
+}
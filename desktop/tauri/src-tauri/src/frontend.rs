@@ -0,0 +1,253 @@
+//! Serves the frontend embedded in the binary over a `runicorn-ui` protocol,
+//! so the window never depends on a dist folder being found on disk.
+//!
+//! `/api/...` requests are forwarded to the running backend with the auth
+//! token; the page learns the backend's address (for its log WebSocket) from
+//! `/__runicorn/backend.js`, which is generated per request so it survives
+//! backend restarts. `RUNICORN_FRONTEND_DIST` switches back to letting the
+//! backend serve a dist folder, for frontend development.
+//!
+//! With a Vite dev server (`RUNICORN_DEV_FRONTEND`, or one found on
+//! localhost:5173 in debug builds) the windows load that instead, and an
+//! initialization script sends the page's `/api` calls straight to the backend.
+
+use std::{
+    io::Read,
+    net::TcpStream,
+    sync::OnceLock,
+    time::Duration,
+};
+
+use serde_json::json;
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager, Url, WebviewWindowBuilder, Wry};
+
+use crate::AppState;
+
+/// Not `runicorn`: that scheme belongs to deep links.
+pub const SCHEME: &str = "runicorn-ui";
+const BACKEND_SCRIPT: &str = "/__runicorn/backend.js";
+const DEFAULT_DEV_SERVER: &str = "http://localhost:5173/";
+const DEV_PROBE_TIMEOUT: Duration = Duration::from_millis(300);
+
+// Asks the app for the backend on every page load (the backend may have moved
+// since the window was made) and rewrites same-origin `/api` fetches to it.
+const DEV_SCRIPT: &str = r#"(() => {
+  const invoke = window.__TAURI_INTERNALS__ && window.__TAURI_INTERNALS__.invoke;
+  if (!invoke) return;
+  const ready = invoke("get_backend_info").then((backend) => {
+    window.__RUNICORN_BACKEND__ = backend;
+    return backend;
+  }, () => null);
+  const original = window.fetch.bind(window);
+  window.fetch = async (input, init) => {
+    const raw = input instanceof Request ? input.url : String(input);
+    const url = new URL(raw, location.href);
+    if (url.origin !== location.origin || !(url.pathname === "/api" || url.pathname.startsWith("/api/"))) {
+      return original(input, init);
+    }
+    const backend = await ready;
+    if (!backend) return original(input, init);
+    const target = backend.url.replace(/\/$/, "") + url.pathname + url.search;
+    const request = new Request(target, input instanceof Request ? input : undefined);
+    const headers = new Headers(init && init.headers ? init.headers : request.headers);
+    if (backend.token) headers.set("X-Runicorn-Token", backend.token);
+    return original(new Request(request, { ...init, headers }));
+  };
+})();"#;
+
+// Hop-by-hop or handled by the proxy itself.
+const SKIPPED_HEADERS: [&str; 6] = ["host", "origin", "cookie", "connection", "content-length", "transfer-encoding"];
+
+/// The Vite dev server the windows load instead of a built frontend:
+/// `RUNICORN_DEV_FRONTEND` (empty turns it off), else localhost:5173 in debug
+/// builds when something is listening there. Decided once per launch.
+pub fn dev_server() -> Option<&'static Url> {
+    static DEV_SERVER: OnceLock<Option<Url>> = OnceLock::new();
+    DEV_SERVER
+        .get_or_init(|| match std::env::var("RUNICORN_DEV_FRONTEND") {
+            Ok(raw) if raw.trim().is_empty() => None,
+            Ok(raw) => match raw.trim().parse::<Url>() {
+                Ok(url) if matches!(url.scheme(), "http" | "https") => Some(url),
+                _ => {
+                    log::warn!("ignoring RUNICORN_DEV_FRONTEND={}: not an http(s) URL", raw);
+                    None
+                }
+            },
+            Err(_) if cfg!(debug_assertions) => {
+                let url = Url::parse(DEFAULT_DEV_SERVER).expect("static dev server is a valid URL");
+                listening(&url).then_some(url)
+            }
+            Err(_) => None,
+        })
+        .as_ref()
+}
+
+fn listening(url: &Url) -> bool {
+    let Ok(addrs) = url.socket_addrs(|| None) else { return false };
+    addrs.iter().any(|addr| TcpStream::connect_timeout(addr, DEV_PROBE_TIMEOUT).is_ok())
+}
+
+/// Adds the dev server's `/api` redirect to a window that may load it.
+pub fn prepare<'a>(
+    builder: WebviewWindowBuilder<'a, Wry, AppHandle>,
+) -> WebviewWindowBuilder<'a, Wry, AppHandle> {
+    if dev_server().is_none() {
+        return builder;
+    }
+    builder.initialization_script(DEV_SCRIPT)
+}
+
+/// True when the window loads the embedded frontend instead of the backend's.
+pub fn embedded(app: &AppHandle) -> bool {
+    if dev_server().is_some() {
+        return false;
+    }
+    if std::env::var_os("RUNICORN_FRONTEND_DIST").is_some_and(|v| !v.is_empty()) {
+        return false;
+    }
+    app.asset_resolver().get("index.html".to_string()).is_some()
+}
+
+/// Origin of the embedded frontend; Windows' webview only allows custom
+/// protocols as `http://<scheme>.localhost`.
+pub fn origin() -> Url {
+    let origin = if cfg!(windows) { format!("http://{}.localhost/", SCHEME) } else { format!("{}://localhost/", SCHEME) };
+    Url::parse(&origin).expect("static origin is a valid URL")
+}
+
+/// Whether `url` points at the embedded frontend or the dev server.
+pub fn is_origin(url: &Url) -> bool {
+    if dev_server().is_some_and(|dev| dev.origin() == url.origin()) {
+        return true;
+    }
+    let origin = origin();
+    url.scheme() == origin.scheme() && url.host_str() == origin.host_str()
+}
+
+/// Handler for the `runicorn-ui` protocol. Runs off the webview thread since
+/// proxied calls block on the backend.
+pub fn handle(app: &AppHandle, request: Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let path = request.uri().path();
+    if path == BACKEND_SCRIPT {
+        return respond(StatusCode::OK, "text/javascript", backend_script(app).into_bytes());
+    }
+    if path == "/api" || path.starts_with("/api/") {
+        return proxy(app, &request);
+    }
+    asset(app, path)
+}
+
+/// `{ url, token }` of the running backend, `None` while there is none.
+pub fn backend_info(app: &AppHandle) -> Option<serde_json::Value> {
+    let state: tauri::State<AppState> = app.state();
+    let url = state.backend_url.lock().unwrap().clone()?;
+    Some(json!({ "url": url, "token": crate::auth::current() }))
+}
+
+/// Sets `window.__RUNICORN_BACKEND__` before any page script runs, as of
+/// window creation. A later backend restart may move it, so pages elsewhere
+/// than that backend or the embedded frontend (which reloads
+/// [`BACKEND_SCRIPT`] anyway) don't get it.
+pub fn init_script(app: &AppHandle) -> Option<String> {
+    let backend = backend_info(app)?;
+    Some(format!(
+        r#"(() => {{
+  const backend = {};
+  const here = location.origin;
+  if (here !== new URL(backend.url).origin && here !== new URL({}).origin) return;
+  window.__RUNICORN_BACKEND__ = backend;
+}})();"#,
+        backend,
+        json!(origin().as_str())
+    ))
+}
+
+fn backend_script(app: &AppHandle) -> String {
+    format!("window.__RUNICORN_BACKEND__ = {};\n", backend_info(app).unwrap_or_default())
+}
+
+fn asset(app: &AppHandle, path: &str) -> Response<Vec<u8>> {
+    let resolver = app.asset_resolver();
+    let key = path.trim_start_matches('/').to_string();
+    // Client-side routes like /runs/<id> have no file; the SPA handles them.
+    let is_route = !key.rsplit('/').next().unwrap_or_default().contains('.');
+    let found = resolver.get(key).or_else(|| is_route.then(|| resolver.get("index.html".to_string())).flatten());
+    let Some(asset) = found else {
+        return respond(StatusCode::NOT_FOUND, "text/plain", b"Not found".to_vec());
+    };
+    let mut body = asset.bytes;
+    if asset.mime_type.starts_with("text/html") {
+        body = inject_backend_script(&body);
+    }
+    let mut response = respond(StatusCode::OK, &asset.mime_type, body);
+    if let Some(policy) = crate::navigation::content_security_policy(app) {
+        if let Ok(value) = policy.parse() {
+            response.headers_mut().insert(header::CONTENT_SECURITY_POLICY, value);
+        }
+    }
+    response
+}
+
+/// Loads the backend script ahead of the app's module scripts.
+fn inject_backend_script(html: &[u8]) -> Vec<u8> {
+    let html = String::from_utf8_lossy(html);
+    let tag = format!("<script src=\"{}\"></script>", BACKEND_SCRIPT);
+    match html.find("<head>") {
+        Some(i) => {
+            let at = i + "<head>".len();
+            format!("{}{}{}", &html[..at], tag, &html[at..]).into_bytes()
+        }
+        None => format!("{}{}", tag, html).into_bytes(),
+    }
+}
+
+fn proxy(app: &AppHandle, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let state: tauri::State<AppState> = app.state();
+    let Some(base) = state.backend_url.lock().unwrap().clone() else {
+        return respond(StatusCode::SERVICE_UNAVAILABLE, "text/plain", b"The backend is not running.".to_vec());
+    };
+    let mut url = format!("{}{}", base.trim_end_matches('/'), request.uri().path());
+    if let Some(query) = request.uri().query() {
+        url.push('?');
+        url.push_str(query);
+    }
+    let mut outgoing = crate::auth::request(request.method().as_str(), &url);
+    for (name, value) in request.headers() {
+        if SKIPPED_HEADERS.contains(&name.as_str()) {
+            continue;
+        }
+        if let Ok(value) = value.to_str() {
+            outgoing = outgoing.set(name.as_str(), value);
+        }
+    }
+    let reply = match outgoing.send_bytes(request.body()) {
+        Ok(reply) => reply,
+        // Error statuses are still answers the page should see.
+        Err(ureq::Error::Status(_, reply)) => reply,
+        Err(e) => {
+            log::warn!("proxying {} failed: {}", url, e);
+            return respond(StatusCode::BAD_GATEWAY, "text/plain", format!("The backend did not answer: {}", e).into_bytes());
+        }
+    };
+    let status = StatusCode::from_u16(reply.status()).unwrap_or(StatusCode::BAD_GATEWAY);
+    let mut builder = Response::builder().status(status);
+    for name in reply.headers_names() {
+        if SKIPPED_HEADERS.contains(&name.as_str()) || name == "set-cookie" {
+            continue;
+        }
+        for value in reply.all(&name) {
+            builder = builder.header(name.as_str(), value);
+        }
+    }
+    let mut body = Vec::new();
+    if let Err(e) = reply.into_reader().read_to_end(&mut body) {
+        log::warn!("reading the backend reply for {} failed: {}", url, e);
+        return respond(StatusCode::BAD_GATEWAY, "text/plain", b"The backend reply was cut off.".to_vec());
+    }
+    builder.body(body).unwrap_or_default()
+}
+
+fn respond(status: StatusCode, content_type: &str, body: Vec<u8>) -> Response<Vec<u8>> {
+    Response::builder().status(status).header(header::CONTENT_TYPE, content_type).body(body).unwrap_or_default()
+}
@@ -0,0 +1,55 @@
+//! A global shortcut that shows or hides the main window from anywhere, even
+//! while it sits in the tray. Set with `global_shortcut` in runicorn.toml.
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::AppState;
+
+pub const DEFAULT_SHORTCUT: &str = "CmdOrCtrl+Alt+R";
+
+/// Registers the configured shortcut. Failures (a bad combo, or one another
+/// app already holds) are logged; the app works without it.
+pub fn register(app: &AppHandle) {
+    let state: tauri::State<AppState> = app.state();
+    let Some(combo) = state.config.global_shortcut() else {
+        log::info!("global shortcut disabled");
+        return;
+    };
+    let shortcut: Shortcut = match combo.parse() {
+        Ok(shortcut) => shortcut,
+        Err(e) => {
+            log::warn!("ignoring invalid global shortcut {:?}: {}", combo, e);
+            return;
+        }
+    };
+    let registered = app.global_shortcut().on_shortcut(shortcut, |app, _, event| {
+        if event.state == ShortcutState::Pressed {
+            toggle(app);
+        }
+    });
+    match registered {
+        Ok(()) => log::info!("global shortcut {} toggles the main window", combo),
+        Err(e) => log::warn!("could not register global shortcut {} (another app may be using it): {}", combo, e),
+    }
+}
+
+/// Hides the main window if it is in front, else brings it up.
+fn toggle(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(crate::MAIN_WINDOW) else {
+        crate::show_main_window(app);
+        return;
+    };
+    let in_front = window.is_visible().unwrap_or(false)
+        && !window.is_minimized().unwrap_or(false)
+        && window.is_focused().unwrap_or(false);
+    if !in_front {
+        crate::show_main_window(app);
+        return;
+    }
+    // Without a tray icon a hidden window could only come back through the shortcut.
+    let result = if app.tray_by_id(crate::tray::TRAY_ID).is_some() { window.hide() } else { window.minimize() };
+    if let Err(e) = result {
+        log::warn!("failed to hide the main window: {}", e);
+    }
+}
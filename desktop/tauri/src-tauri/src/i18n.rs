@@ -0,0 +1,397 @@
+//! Text for the native menus, tray, dialogs, notifications and the app's own
+//! pages, in English or Simplified Chinese. The language comes from `RUNICORN_LANG` (`en`, `zh`, `zh-CN`, …),
+//! else the OS locale; keys with no translation fall back to English.
+
+use std::{fmt::Display, sync::OnceLock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    En,
+    Zh,
+}
+
+const EN: &[(&str, &str)] = &[
+    ("dialog.title", "Runicorn"),
+    ("dialog.cancel", "Cancel"),
+    ("menu.file", "File"),
+    ("menu.open_storage_dir", "Open Storage Folder…"),
+    ("menu.edit", "Edit"),
+    ("menu.view", "View"),
+    ("menu.backend", "Backend"),
+    ("menu.window", "Window"),
+    ("menu.reload", "Reload"),
+    ("menu.zoom_in", "Zoom In"),
+    ("menu.zoom_out", "Zoom Out"),
+    ("menu.actual_size", "Actual Size"),
+    ("menu.devtools", "Toggle Developer Tools"),
+    ("menu.help", "Help"),
+    ("menu.check_updates", "Check for Updates…"),
+    ("tray.tooltip", "Runicorn"),
+    ("tray.tooltip_one", "Runicorn — 1 active run"),
+    ("tray.tooltip_many", "Runicorn — {} active runs"),
+    ("tray.show", "Show Runicorn"),
+    ("tray.keep_on_top", "Keep on Top"),
+    ("tray.restart_backend", "Restart Backend"),
+    ("tray.stop_backend", "Stop Backend"),
+    ("tray.log_level", "Backend Log Level"),
+    ("tray.profiles", "Profiles"),
+    ("tray.no_profiles", "No Saved Profiles"),
+    ("tray.recent_dirs", "Recent Folders"),
+    ("tray.no_recent_dirs", "No Recent Folders"),
+    ("tray.recent_dir_removed", "{}\n\nIt has been removed from Recent Folders."),
+    ("tray.use_local", "Use Local Backend"),
+    ("tray.copy_url", "Copy Backend URL"),
+    ("tray.open_logs", "Open Logs Folder"),
+    ("tray.check_updates", "Check for Updates…"),
+    ("tray.autostart", "Launch at Login"),
+    ("tray.copy_diagnostics", "Copy Diagnostics"),
+    ("tray.copy_diagnostics_failed", "Could not copy diagnostics: {}"),
+    ("tray.about", "About Runicorn"),
+    ("tray.restart_app", "Restart Runicorn"),
+    ("tray.quit", "Quit"),
+    ("about.title", "About Runicorn"),
+    ("about.copy", "Copy"),
+    ("about.close", "Close"),
+    (
+        "quit.confirm_one",
+        "A run is still running or about to start. Quitting stops the backend, and the viewer won't pick up new data \
+         until Runicorn is opened again.\n\nQuit anyway?",
+    ),
+    (
+        "quit.confirm_many",
+        "{} runs are still running or about to start. Quitting stops the backend, and the viewer won't pick up new data \
+         until Runicorn is opened again.\n\nQuit anyway?",
+    ),
+    ("quit.anyway", "Quit Anyway"),
+    (
+        "stop.confirm_one",
+        "A run is still running or about to start. Stopping the backend frees its port, and the viewer won't pick up new \
+         data until it is started again.\n\nStop anyway?",
+    ),
+    (
+        "stop.confirm_many",
+        "{} runs are still running or about to start. Stopping the backend frees its port, and the viewer won't pick up new \
+         data until it is started again.\n\nStop anyway?",
+    ),
+    ("stop.anyway", "Stop Anyway"),
+    (
+        "port.other_runicorn",
+        "Another Runicorn backend is already running on port {}, so this window uses its own on port {}.\n\n\
+         Switch to the one on port {}? It shows the runs in its own storage folder.",
+    ),
+    (
+        "port.other_window",
+        "Another Runicorn window's backend is already running on port {}, so this window uses its own on port {}. \
+         Each window keeps its backend private, so the two stay separate.",
+    ),
+    ("port.use_it", "Use It"),
+    ("port.keep_separate", "Keep Separate"),
+    (
+        "port.other_program",
+        "Port {} is used by another program, so Runicorn is running on port {} today. \
+         Everything works the same; only the address differs.",
+    ),
+    (
+        "frontend.missing",
+        "Runicorn's backend is running, but no frontend was found to show, so the window will stay blank.\n\n\
+         Build it with `npm run build` in web/frontend and rebuild the app, or set RUNICORN_FRONTEND_DIST \
+         to a built dist folder.",
+    ),
+    (
+        "watchdog.gave_up",
+        "{}\n\nThe backend keeps crashing, so it was not restarted again. Check the backend log, then use \
+         Restart Backend from the tray menu.",
+    ),
+    ("watchdog.restart_failed", "The backend crashed and could not be restarted.\n\n{}"),
+    ("watchdog.exited", "The backend process exited unexpectedly."),
+    ("watchdog.unhealthy", "The backend failed {} health checks in a row."),
+    ("update.title", "Runicorn Update"),
+    ("update.latest", "You're on the latest version ({})."),
+    ("update.available", "Update {} is available (you have {}).\n\nInstall it now? Runicorn restarts when it's done."),
+    ("update.install", "Install and Restart"),
+    ("update.later", "Later"),
+    ("update.offline", "Couldn't check for updates (offline?)."),
+    ("update.failed", "Couldn't check for updates."),
+    ("notify.finished", "Run finished"),
+    ("notify.failed", "Run failed"),
+    ("notify.finished_many", "{} runs finished"),
+    ("splash.starting", "Starting…"),
+    ("splash.starting_port", "Starting backend on port {}…"),
+    ("splash.polling", "Waiting for the backend… (attempt {})"),
+    ("splash.ready", "Backend ready"),
+    ("spawn.sidecar_missing", "The bundled Runicorn backend is missing ({}). Reinstall the app to restore it."),
+    (
+        "spawn.sidecar_tampered",
+        "Runicorn refused to start its bundled backend because it has been modified or corrupted ({}).\n\n\
+         Reinstall the app to restore it.",
+    ),
+    (
+        "spawn.python_too_old",
+        "Runicorn could not start its backend: no bundled backend was found and `{}` is Python {}.{}, \
+         but Runicorn needs Python {}.{} or newer.\n\n\
+         Upgrade Python and run `pip install runicorn`, or point RUNICORN_DESKTOP_PY at a newer interpreter.",
+    ),
+    (
+        "spawn.python_missing",
+        "Runicorn could not start its backend: no bundled backend was found and no Python {}.{}+ is available \
+         (tried: {}).\n\n\
+         Install Python {}.{}+ and run `pip install runicorn`, point RUNICORN_DESKTOP_PY at an existing interpreter, \
+         or reinstall the app.",
+    ),
+    ("spawn.failed", "Runicorn could not start its backend ({}): {}"),
+    ("port.in_use", "Port {} was requested via {} but is already in use by another program."),
+    ("ready.ready", "ready after {}"),
+    ("ready.timeout", "The backend did not respond within {} seconds."),
+    ("ready.http_error", "The backend answered with HTTP {} instead of reporting healthy."),
+    ("ready.exited", "The backend process exited after {}, before it reported healthy."),
+    ("ready.foreign", "The port is used by another app: it answered, but not as Runicorn's backend."),
+    ("ready.cancelled", "Startup was cancelled."),
+    ("startup.port", "{}\n\nFree the port or choose a different one, then start Runicorn again."),
+    (
+        "startup.not_ready",
+        "Runicorn's backend failed to start on port {}.\n\n{}\n\nSee backend.log in the app's log folder for details.",
+    ),
+    ("startup.window", "Runicorn's backend is running, but {}."),
+    (
+        "startup.remote",
+        "Runicorn could not reach the remote backend at {}: {}.\n\n\
+         Check that it is running and reachable, or choose Use Local Backend from the tray menu.",
+    ),
+    (
+        "startup.no_backend_url",
+        "Runicorn is set not to start its own backend (RUNICORN_NO_SPAWN or spawn_backend = false), \
+         and no backend URL is configured.\n\n\
+         Set RUNICORN_BACKEND_URL or backend_url in runicorn.toml, or connect to one from the start page.",
+    ),
+    ("startup.cancelled", "Startup was cancelled because Runicorn is closing."),
+    // The pages' text may contain markup; it is inserted as is.
+    ("page.error_title", "Runicorn could not start"),
+    ("page.stopped_title", "Backend stopped"),
+    ("page.stopped_text", "The Runicorn backend is not running, and its port is free for other programs."),
+    ("page.start_again", "Start again"),
+    ("page.connect_title", "Connect to a backend"),
+    (
+        "page.connect_text",
+        "This app is set not to start its own backend. Enter the address of a running Runicorn backend, \
+         e.g. <code>http://127.0.0.1:23300</code>; it is remembered for the next launch.",
+    ),
+    ("page.connect", "Connect"),
+    ("page.no_backend_title", "No backend available"),
+    (
+        "page.no_backend_text",
+        "Runicorn needs a backend to show your runs, but this installation has no bundled backend and no usable Python. \
+         Any one of these gets you going:",
+    ),
+    (
+        "page.no_backend_python",
+        "<strong>Install Python {{min_python}} or newer</strong>, then run <code>pip install runicorn</code> and press Retry. \
+         To use a Python that isn't on <code>PATH</code>, set <code>RUNICORN_DESKTOP_PY</code> to it.",
+    ),
+    ("page.no_backend_reinstall", "<strong>Reinstall Runicorn</strong> to restore its bundled backend, which needs no Python."),
+    (
+        "page.no_backend_connect",
+        "<strong>Connect to a backend running elsewhere</strong>, e.g. <code>runicorn viewer</code> on a server:",
+    ),
+    ("page.details", "Details"),
+    ("page.retry", "Retry"),
+    ("page.copied", "Copied"),
+];
+
+const ZH: &[(&str, &str)] = &[
+    ("dialog.cancel", "取消"),
+    ("menu.file", "文件"),
+    ("menu.open_storage_dir", "打开存储文件夹…"),
+    ("menu.edit", "编辑"),
+    ("menu.view", "视图"),
+    ("menu.backend", "后端"),
+    ("menu.window", "窗口"),
+    ("menu.reload", "重新加载"),
+    ("menu.zoom_in", "放大"),
+    ("menu.zoom_out", "缩小"),
+    ("menu.actual_size", "实际大小"),
+    ("menu.devtools", "切换开发者工具"),
+    ("menu.help", "帮助"),
+    ("menu.check_updates", "检查更新…"),
+    ("tray.tooltip_one", "Runicorn — 1 个运行中"),
+    ("tray.tooltip_many", "Runicorn — {} 个运行中"),
+    ("tray.show", "显示 Runicorn"),
+    ("tray.keep_on_top", "窗口置顶"),
+    ("tray.restart_backend", "重启后端"),
+    ("tray.stop_backend", "停止后端"),
+    ("tray.log_level", "后端日志级别"),
+    ("tray.profiles", "配置"),
+    ("tray.no_profiles", "没有已保存的配置"),
+    ("tray.recent_dirs", "最近的文件夹"),
+    ("tray.no_recent_dirs", "没有最近的文件夹"),
+    ("tray.recent_dir_removed", "{}\n\n已将其从最近的文件夹中移除。"),
+    ("tray.use_local", "使用本地后端"),
+    ("tray.copy_url", "复制后端地址"),
+    ("tray.open_logs", "打开日志文件夹"),
+    ("tray.check_updates", "检查更新…"),
+    ("tray.autostart", "登录时启动"),
+    ("tray.copy_diagnostics", "复制诊断信息"),
+    ("tray.copy_diagnostics_failed", "无法复制诊断信息：{}"),
+    ("tray.about", "关于 Runicorn"),
+    ("tray.restart_app", "重启 Runicorn"),
+    ("tray.quit", "退出"),
+    ("about.title", "关于 Runicorn"),
+    ("about.copy", "复制"),
+    ("about.close", "关闭"),
+    ("quit.confirm_one", "有 1 个运行仍在运行或即将开始。退出会停止后端，在重新打开 Runicorn 之前查看器不会获取新数据。\n\n仍然退出吗？"),
+    ("quit.confirm_many", "有 {} 个运行仍在运行或即将开始。退出会停止后端，在重新打开 Runicorn 之前查看器不会获取新数据。\n\n仍然退出吗？"),
+    ("quit.anyway", "仍然退出"),
+    ("stop.confirm_one", "有 1 个运行仍在运行或即将开始。停止后端会释放其端口，在重新启动之前查看器不会获取新数据。\n\n仍然停止吗？"),
+    ("stop.confirm_many", "有 {} 个运行仍在运行或即将开始。停止后端会释放其端口，在重新启动之前查看器不会获取新数据。\n\n仍然停止吗？"),
+    ("stop.anyway", "仍然停止"),
+    (
+        "port.other_runicorn",
+        "端口 {} 上已有另一个 Runicorn 后端在运行，因此本窗口使用端口 {} 上自己的后端。\n\n\
+         要切换到端口 {} 上的后端吗？它显示的是其自身存储文件夹中的运行。",
+    ),
+    ("port.other_window", "另一个 Runicorn 窗口的后端已在端口 {} 上运行，因此本窗口使用端口 {} 上自己的后端。每个窗口的后端仅供其自身使用，两者互相独立。"),
+    ("port.use_it", "切换"),
+    ("port.keep_separate", "保持独立"),
+    ("port.other_program", "端口 {} 被其他程序占用，因此这次 Runicorn 运行在端口 {} 上。功能完全相同，只是地址不同。"),
+    (
+        "frontend.missing",
+        "Runicorn 后端正在运行，但找不到可显示的前端，窗口将保持空白。\n\n\
+         请在 web/frontend 中运行 `npm run build` 并重新构建应用，或将 RUNICORN_FRONTEND_DIST 设为已构建的 dist 文件夹。",
+    ),
+    ("watchdog.gave_up", "{}\n\n后端反复崩溃，因此不再自动重启。请检查后端日志，然后使用托盘菜单中的“重启后端”。"),
+    ("watchdog.restart_failed", "后端崩溃且无法重启。\n\n{}"),
+    ("watchdog.exited", "后端进程意外退出。"),
+    ("watchdog.unhealthy", "后端连续 {} 次健康检查失败。"),
+    ("update.title", "Runicorn 更新"),
+    ("update.latest", "已是最新版本（{}）。"),
+    ("update.available", "有可用更新 {}（当前版本 {}）。\n\n现在安装吗？安装完成后 Runicorn 将重新启动。"),
+    ("update.install", "安装并重启"),
+    ("update.later", "稍后"),
+    ("update.offline", "无法检查更新（可能处于离线状态）。"),
+    ("update.failed", "无法检查更新。"),
+    ("notify.finished", "运行已完成"),
+    ("notify.failed", "运行失败"),
+    ("notify.finished_many", "{} 个运行已完成"),
+    ("splash.starting", "正在启动…"),
+    ("splash.starting_port", "正在端口 {} 上启动后端…"),
+    ("splash.polling", "正在等待后端…（第 {} 次尝试）"),
+    ("splash.ready", "后端已就绪"),
+    ("spawn.sidecar_missing", "内置的 Runicorn 后端缺失（{}）。请重新安装应用以恢复。"),
+    ("spawn.sidecar_tampered", "内置后端已被修改或损坏，Runicorn 拒绝启动它（{}）。\n\n请重新安装应用以恢复。"),
+    (
+        "spawn.python_too_old",
+        "Runicorn 无法启动后端：未找到内置后端，且 `{}` 是 Python {}.{}，而 Runicorn 需要 Python {}.{} 或更高版本。\n\n\
+         请升级 Python 并运行 `pip install runicorn`，或将 RUNICORN_DESKTOP_PY 指向更新的解释器。",
+    ),
+    (
+        "spawn.python_missing",
+        "Runicorn 无法启动后端：未找到内置后端，也没有可用的 Python {}.{}+（已尝试：{}）。\n\n\
+         请安装 Python {}.{}+ 并运行 `pip install runicorn`，将 RUNICORN_DESKTOP_PY 指向已有的解释器，或重新安装应用。",
+    ),
+    ("spawn.failed", "Runicorn 无法启动后端（{}）：{}"),
+    ("port.in_use", "端口 {}（由 {} 指定）已被其他程序占用。"),
+    ("ready.ready", "{} 后就绪"),
+    ("ready.timeout", "后端在 {} 秒内没有响应。"),
+    ("ready.http_error", "后端返回了 HTTP {}，而不是健康状态。"),
+    ("ready.exited", "后端进程在 {} 后退出，未能报告健康状态。"),
+    ("ready.foreign", "该端口被其他应用占用：它有响应，但不是 Runicorn 的后端。"),
+    ("ready.cancelled", "启动已取消。"),
+    ("startup.port", "{}\n\n请释放该端口或选择其他端口，然后重新启动 Runicorn。"),
+    ("startup.not_ready", "Runicorn 后端无法在端口 {} 上启动。\n\n{}\n\n详情请查看应用日志文件夹中的 backend.log。"),
+    ("startup.window", "Runicorn 后端正在运行，但 {}。"),
+    ("startup.remote", "Runicorn 无法连接到 {} 上的远程后端：{}。\n\n请确认它正在运行且可以访问，或在托盘菜单中选择“使用本地后端”。"),
+    (
+        "startup.no_backend_url",
+        "Runicorn 被设置为不启动自己的后端（RUNICORN_NO_SPAWN 或 spawn_backend = false），且未配置后端地址。\n\n\
+         请设置 RUNICORN_BACKEND_URL 或 runicorn.toml 中的 backend_url，或在起始页连接到一个后端。",
+    ),
+    ("startup.cancelled", "Runicorn 正在关闭，启动已取消。"),
+    ("page.error_title", "Runicorn 无法启动"),
+    ("page.stopped_title", "后端已停止"),
+    ("page.stopped_text", "Runicorn 后端没有运行，其端口可供其他程序使用。"),
+    ("page.start_again", "重新启动"),
+    ("page.connect_title", "连接到后端"),
+    (
+        "page.connect_text",
+        "此应用被设置为不启动自己的后端。请输入正在运行的 Runicorn 后端地址，例如 <code>http://127.0.0.1:23300</code>；下次启动时会记住它。",
+    ),
+    ("page.connect", "连接"),
+    ("page.no_backend_title", "没有可用的后端"),
+    ("page.no_backend_text", "Runicorn 需要后端来显示你的运行，但此安装既没有内置后端，也没有可用的 Python。以下任一方式均可解决："),
+    (
+        "page.no_backend_python",
+        "<strong>安装 Python {{min_python}} 或更高版本</strong>，然后运行 <code>pip install runicorn</code> 并点击“重试”。\
+         要使用不在 <code>PATH</code> 中的 Python，请将 <code>RUNICORN_DESKTOP_PY</code> 设为它。",
+    ),
+    ("page.no_backend_reinstall", "<strong>重新安装 Runicorn</strong>，以恢复无需 Python 的内置后端。"),
+    ("page.no_backend_connect", "<strong>连接到其他地方运行的后端</strong>，例如服务器上的 <code>runicorn viewer</code>："),
+    ("page.details", "详细信息"),
+    ("page.retry", "重试"),
+    ("page.copied", "已复制"),
+];
+
+fn parse(tag: &str) -> Option<Lang> {
+    let tag = tag.trim().to_ascii_lowercase();
+    if tag.starts_with("zh") {
+        Some(Lang::Zh)
+    } else if tag.starts_with("en") {
+        Some(Lang::En)
+    } else {
+        None
+    }
+}
+
+/// The language for this launch: `RUNICORN_LANG`, else the OS locale, else English.
+pub fn lang() -> Lang {
+    static LANG: OnceLock<Lang> = OnceLock::new();
+    *LANG.get_or_init(|| {
+        if let Ok(raw) = std::env::var("RUNICORN_LANG") {
+            match parse(&raw) {
+                Some(lang) => return lang,
+                None => log::warn!("ignoring RUNICORN_LANG={}: use en or zh", raw),
+            }
+        }
+        // Other languages get English rather than nothing.
+        sys_locale::get_locale().as_deref().and_then(parse).unwrap_or(Lang::En)
+    })
+}
+
+fn lookup(table: &[(&str, &'static str)], key: &str) -> Option<&'static str> {
+    table.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+}
+
+/// The text for `key` in the current language; the key itself if even
+/// English lacks it.
+pub fn t(key: &'static str) -> &'static str {
+    let translated = match lang() {
+        Lang::Zh => lookup(ZH, key),
+        Lang::En => None,
+    };
+    translated.or_else(|| lookup(EN, key)).unwrap_or(key)
+}
+
+/// [`t`] with each `{}` replaced by the next of `args`.
+pub fn tf(key: &'static str, args: &[&dyn Display]) -> String {
+    let mut parts = t(key).split("{}");
+    let mut out = parts.next().unwrap_or_default().to_string();
+    for (i, part) in parts.enumerate() {
+        if let Some(arg) = args.get(i) {
+            out.push_str(&arg.to_string());
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+/// `page` with each `{{t:key}}` replaced by [`t`] of that key.
+pub fn localize(page: &'static str) -> String {
+    let mut out = String::with_capacity(page.len());
+    let mut rest = page;
+    while let Some(start) = rest.find("{{t:") {
+        let Some(len) = rest[start..].find("}}") else { break };
+        out.push_str(&rest[..start]);
+        out.push_str(t(&rest[start + 4..start + len]));
+        rest = &rest[start + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,118 @@
+//! Checks the sidecar before it is run, so a replaced or corrupted
+//! `runicorn-viewer` is refused instead of executed. Unsigned builds compare
+//! it with the SHA-256 baked in by build.rs; code-signed builds, whose sidecar
+//! the bundler rewrites when signing it, require it to carry a valid signature
+//! from the same signer as the app. Builds without a bundled sidecar (dev
+//! builds) skip the check.
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use std::process::{Command, Stdio};
+use std::{fs::File, io, path::Path, time::Instant};
+
+use sha2::{Digest, Sha256};
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use crate::process::hide_console;
+
+const EXPECTED: &str = env!("RUNICORN_SIDECAR_SHA256");
+const SIGNED: &str = env!("RUNICORN_SIDECAR_SIGNED");
+
+/// The hash the sidecar must have, `None` when this build doesn't check one.
+pub fn expected() -> Option<&'static str> {
+    (!EXPECTED.is_empty()).then_some(EXPECTED)
+}
+
+/// How the sidecar is checked, for diagnostics.
+pub fn method() -> &'static str {
+    match expected() {
+        Some(hash) => hash,
+        None if !SIGNED.is_empty() => "code signature",
+        None => "not checked",
+    }
+}
+
+fn sha256(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Stdout and stderr of a helper command, or why it failed.
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let mut cmd = Command::new(program);
+    cmd.args(args).stdin(Stdio::null());
+    hide_console(&mut cmd);
+    let out = cmd.output().map_err(|e| format!("{} could not be run: {}", program, e))?;
+    let text = format!("{}{}", String::from_utf8_lossy(&out.stdout), String::from_utf8_lossy(&out.stderr));
+    if !out.status.success() {
+        return Err(text.trim().to_string());
+    }
+    Ok(text)
+}
+
+/// The Team ID `path` is signed with, after `codesign` accepted the signature.
+#[cfg(target_os = "macos")]
+fn signer(path: &Path) -> Result<String, String> {
+    let path = path.to_string_lossy();
+    run("codesign", &["--verify", "--strict", &path])?;
+    let details = run("codesign", &["-dv", "--verbose=2", &path])?;
+    details
+        .lines()
+        .find_map(|line| line.strip_prefix("TeamIdentifier="))
+        .map(str::trim)
+        .filter(|team| *team != "not set")
+        .map(str::to_string)
+        .ok_or_else(|| format!("{} has no Team ID", path))
+}
+
+/// The certificate thumbprint `path` is signed with, after Windows accepted
+/// its Authenticode signature.
+#[cfg(target_os = "windows")]
+fn signer(path: &Path) -> Result<String, String> {
+    let literal = path.to_string_lossy().replace('\'', "''");
+    let script = format!(
+        "$s = Get-AuthenticodeSignature -LiteralPath '{}'; if ($s.Status -ne 'Valid') {{ Write-Output $s.StatusMessage; exit 1 }}; $s.SignerCertificate.Thumbprint",
+        literal
+    );
+    let thumbprint = run("powershell", &["-NoProfile", "-NonInteractive", "-Command", &script])?;
+    Ok(thumbprint.trim().to_string())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn signer(_path: &Path) -> Result<String, String> {
+    Err("code signatures can't be checked on this platform".to_string())
+}
+
+/// Checks that `path` is signed by whoever signed this app.
+fn verify_signature(path: &Path) -> Result<(), String> {
+    let app = std::env::current_exe().map_err(|e| format!("the app's own path is unknown: {}", e))?;
+    let ours = signer(&app).map_err(|e| format!("the app's own signature could not be read: {}", e))?;
+    let theirs = signer(path).map_err(|e| format!("{} has no valid signature: {}", path.display(), e))?;
+    if theirs != ours {
+        return Err(format!("{} is signed by {}, but this app by {}", path.display(), theirs, ours));
+    }
+    Ok(())
+}
+
+/// Checks the sidecar at `path` against the built-in hash, or its signature
+/// in a signed build; the error describes the mismatch.
+pub fn verify(path: &Path) -> Result<(), String> {
+    let started = Instant::now();
+    let Some(expected) = expected() else {
+        if SIGNED.is_empty() || cfg!(not(any(target_os = "macos", target_os = "windows"))) {
+            log::debug!("no sidecar hash built in; skipping the integrity check");
+            return Ok(());
+        }
+        verify_signature(path)?;
+        log::info!("sidecar signature verified in {:.1?}", started.elapsed());
+        return Ok(());
+    };
+    let actual = sha256(path).map_err(|e| format!("{} could not be read to check it: {}", path.display(), e))?;
+    if actual != expected {
+        return Err(format!("{} has SHA-256 {}, but this build expects {}", path.display(), actual, expected));
+    }
+    log::info!("sidecar integrity verified in {:.1?}", started.elapsed());
+    Ok(())
+}
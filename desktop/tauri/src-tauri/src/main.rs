@@ -1,227 +1,2678 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use std::{
-    net::{SocketAddr, TcpStream},
-    process::{Child, Command, Stdio},
+    fmt,
+    net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener},
+    process::{Child, Stdio},
     thread,
     time::{Duration, Instant},
-    sync::Mutex,
-    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    path::{Path, PathBuf},
 };
 
-use tauri::{AppHandle, Manager, WindowEvent, WebviewUrl, WebviewWindowBuilder};
+mod about;
+mod activity;
+mod auth;
+mod autostart;
+mod backend_log;
+mod capability;
+mod config;
+mod deep_link;
+mod diagnostics;
+mod export;
+mod frontend;
+mod hotkey;
+mod i18n;
+mod integrity;
+mod menu;
+mod navigation;
+mod notifications;
+mod pidfile;
+mod platform;
+mod process;
+mod profiles;
+mod proxy;
+mod python;
+mod remote;
+mod resources;
+mod splash;
+mod storage;
+mod timings;
+mod tls;
+mod tray;
+mod updater;
+mod vibrancy;
+mod watchdog;
+mod window_state;
+mod workspace;
+
+use backend_log::BackendLog;
+use serde::Serialize;
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager, WindowEvent, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tauri_plugin_opener::OpenerExt;
 use tauri_plugin_shell::{ShellExt};
-use tauri_plugin_shell::process::CommandChild as ShellChild;
+use tauri_plugin_shell::process::{CommandChild as ShellChild, CommandEvent};
+
+// Address the backend binds to unless RUNICORN_HOST says otherwise.
+const BACKEND_HOST: &str = "127.0.0.1";
 
-fn is_port_available(port: u16) -> bool {
-    let addr = SocketAddr::from(([127, 0, 0, 1], port));
-    TcpStream::connect(addr).is_err()
+/// Address the backend binds to (port probing must use the same one):
+/// `RUNICORN_HOST` if it is an IP address, else [`BACKEND_HOST`].
+fn backend_ip() -> IpAddr {
+    static IP: OnceLock<IpAddr> = OnceLock::new();
+    *IP.get_or_init(|| {
+        let default = BACKEND_HOST.parse().unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let raw = std::env::var("RUNICORN_HOST").unwrap_or_default();
+        let raw = raw.trim().trim_matches(&['[', ']'][..]);
+        if raw.is_empty() {
+            return default;
+        }
+        match raw.parse::<IpAddr>() {
+            Ok(ip) if ip.is_loopback() => ip,
+            Ok(ip) => {
+                log::warn!(
+                    "RUNICORN_HOST={}: the backend accepts connections from other machines. It still requires \
+                     this launch's auth token, but anyone who obtains it can read and change your runs.",
+                    ip
+                );
+                ip
+            }
+            Err(_) => {
+                log::warn!("ignoring RUNICORN_HOST={:?}: not an IP address", raw);
+                default
+            }
+        }
+    })
 }
 
-fn spawn_python_backend(port: u16) -> Option<BackendChild> {
-    let python = std::env::var("RUNICORN_DESKTOP_PY").unwrap_or_else(|_| "python".to_string());
-    let mut cmd = Command::new(python);
-    cmd.args([
-        "-X", "utf8",
-        "-m", "uvicorn",
-        "runicorn.viewer:create_app",
-        "--factory",
-        "--host", "127.0.0.1",
-        "--port", &port.to_string(),
-    ])
-    .stdin(Stdio::null())
-    .stdout(Stdio::null())
-    .stderr(Stdio::null());
-    if let Some(src_dir) = repo_src_dir_guess() {
-        let py_path_key = "PYTHONPATH";
-        let mut val = std::env::var(py_path_key).unwrap_or_default();
-        if !val.is_empty() { if cfg!(target_os = "windows") { val.push(';') } else { val.push(':') } }
-        val.push_str(&src_dir.to_string_lossy());
-        cmd.env(py_path_key, val);
+/// `--host` for the backend.
+fn bind_host() -> String {
+    backend_ip().to_string()
+}
+
+/// Host in the URLs we reach the backend at: the address it binds to, or
+/// loopback when that is "all interfaces".
+fn connect_host() -> String {
+    match backend_ip() {
+        IpAddr::V4(ip) if ip.is_unspecified() => Ipv4Addr::LOCALHOST.to_string(),
+        IpAddr::V6(ip) if ip.is_unspecified() => "[::1]".to_string(),
+        IpAddr::V6(ip) => format!("[{}]", ip),
+        ip => ip.to_string(),
+    }
+}
+
+fn bind_port(port: u16) -> Option<TcpListener> {
+    // Only a successful bind proves the backend can bind too; a refused connect
+    // may just be a firewall.
+    TcpListener::bind(SocketAddr::new(backend_ip(), port)).ok()
+}
+
+/// A chosen port, kept bound until just before the backend spawns so nothing
+/// else can take it in between.
+struct PortReservation {
+    port: u16,
+    listener: TcpListener,
+}
+
+impl PortReservation {
+    fn new(listener: TcpListener) -> Option<Self> {
+        let port = listener.local_addr().ok()?.port();
+        Some(PortReservation { port, listener })
+    }
+
+    /// Frees the port for the backend to bind.
+    fn release(self) -> u16 {
+        drop(self.listener);
+        self.port
+    }
+}
+
+#[derive(Debug)]
+enum SpawnError {
+    /// The bundled `runicorn-viewer` sidecar could not be resolved.
+    SidecarMissing(String),
+    /// The sidecar does not match the hash built into the app, so it was not run.
+    SidecarTampered(String),
+    /// No Python interpreter recent enough for the fallback backend was found.
+    PythonMissing(python::NotFound),
+    /// A backend program was found but the OS refused to start it.
+    Spawn { program: String, source: String },
+}
+
+impl fmt::Display for SpawnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (min_major, min_minor) = python::MIN_VERSION;
+        let text = match self {
+            SpawnError::SidecarMissing(reason) => i18n::tf("spawn.sidecar_missing", &[reason]),
+            SpawnError::SidecarTampered(reason) => i18n::tf("spawn.sidecar_tampered", &[reason]),
+            SpawnError::PythonMissing(python::NotFound { too_old: Some((py, (major, minor))), .. }) => {
+                i18n::tf("spawn.python_too_old", &[py, major, minor, &min_major, &min_minor])
+            }
+            SpawnError::PythonMissing(python::NotFound { tried, .. }) => {
+                i18n::tf("spawn.python_missing", &[&min_major, &min_minor, &tried.join(", "), &min_major, &min_minor])
+            }
+            SpawnError::Spawn { program, source } => i18n::tf("spawn.failed", &[program, source]),
+        };
+        f.write_str(&text)
+    }
+}
+
+/// Splits on whitespace, keeping '...' and "..." quoted segments together.
+fn split_args(raw: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut cur = String::new();
+    let mut quote: Option<char> = None;
+    let mut in_token = false;
+    for c in raw.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => cur.push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                in_token = true;
+            }
+            None if c.is_whitespace() => {
+                if in_token {
+                    args.push(std::mem::take(&mut cur));
+                    in_token = false;
+                }
+            }
+            None => {
+                cur.push(c);
+                in_token = true;
+            }
+        }
     }
-    if let Some(dist) = repo_frontend_dist_guess() {
-        cmd.env("RUNICORN_FRONTEND_DIST", dist.to_string_lossy().as_ref());
+    if in_token {
+        args.push(cur);
     }
-    cmd.spawn().ok().map(BackendChild::Python)
+    args
 }
 
-fn repo_frontend_dist_guess() -> Option<PathBuf> {
-    // Try ../../../web/frontend/dist relative to current dir (dev layout)
-    if let Ok(mut p) = std::env::current_dir() {
-        let mut try1 = p.clone();
-        try1.push("../../../web/frontend/dist");
-        if try1.exists() {
-            return Some(try1);
+// Flags the desktop app controls itself; letting users override them would
+// desync pick_port/wait_ready from where the backend actually listens.
+const RESERVED_BACKEND_ARGS: &[&str] = &["--host", "--port"];
+
+/// Extra backend flags from `RUNICORN_BACKEND_ARGS` (or `backend_args` in
+/// runicorn.toml), appended after `--port`.
+fn extra_backend_args(app: &AppHandle) -> Vec<String> {
+    let (tokens, source) = match std::env::var("RUNICORN_BACKEND_ARGS") {
+        Ok(raw) => (split_args(&raw), "RUNICORN_BACKEND_ARGS"),
+        Err(_) => {
+            let state: tauri::State<AppState> = app.state();
+            (state.config.backend_args.clone(), "backend_args")
         }
-        // Try project root /web/frontend/dist
-        p.push("web/frontend/dist");
-        if p.exists() {
-            return Some(p);
+    };
+    let mut out = Vec::new();
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(tok) = tokens.next() {
+        let flag = tok.split('=').next().unwrap_or_default();
+        if RESERVED_BACKEND_ARGS.contains(&flag) {
+            log::warn!("ignoring {} in {}: host and port are managed by the desktop app", flag, source);
+            // drop the flag's value too when it's passed as a separate token
+            if !tok.contains('=') && tokens.peek().is_some_and(|next| !next.starts_with('-')) {
+                tokens.next();
+            }
+            continue;
         }
+        out.push(tok);
     }
-    None
+    out
 }
 
-fn pick_port() -> u16 {
-    let preferred = 8000u16;
-    if is_port_available(preferred) {
-        return preferred;
+/// Backend verbosities, as uvicorn names them.
+const LOG_LEVELS: [&str; 4] = ["debug", "info", "warning", "error"];
+const DEFAULT_LOG_LEVEL: &str = "info";
+
+fn parse_log_level(raw: &str, source: &str) -> Option<&'static str> {
+    let raw = raw.trim().to_ascii_lowercase();
+    let raw = if raw == "warn" { "warning".to_string() } else { raw };
+    let level = LOG_LEVELS.iter().copied().find(|l| *l == raw);
+    if level.is_none() && !raw.is_empty() {
+        log::warn!("ignoring log level {:?} from {}; expected one of {}", raw, source, LOG_LEVELS.join(", "));
     }
-    // try a few ephemeral ports
-    for p in 49152..=65535 {
-        if is_port_available(p) {
-            return p;
-        }
+    level
+}
+
+/// Level the backend runs at: the one picked from the tray, else
+/// `RUNICORN_LOG_LEVEL`, else `log_level` in runicorn.toml, else `info`.
+fn backend_log_level(state: &AppState) -> &'static str {
+    if let Some(level) = *state.log_level.lock().unwrap() {
+        return level;
     }
-    preferred
+    let from_env = std::env::var("RUNICORN_LOG_LEVEL").ok().and_then(|v| parse_log_level(&v, "RUNICORN_LOG_LEVEL"));
+    let from_config = || state.config.log_level.as_deref().and_then(|v| parse_log_level(v, config::CONFIG_FILE_NAME));
+    from_env.or_else(from_config).unwrap_or(DEFAULT_LOG_LEVEL)
 }
 
-fn repo_src_dir_guess() -> Option<PathBuf> {
-    // Best-effort: try ../../../src relative to this src-tauri binary location during dev
-    if let Ok(mut p) = std::env::current_dir() {
-        // common case: running from desktop/tauri
-        let mut try1 = p.clone();
-        try1.push("../../../src");
-        if try1.exists() {
-            return Some(try1);
-        }
-        // try project root /src
-        p.push("src");
-        if p.exists() {
-            return Some(p);
+/// Switches the backend's log level for the rest of the session and restarts it.
+fn set_backend_log_level_blocking(app: &AppHandle, level: &str) -> Result<String, String> {
+    let level = parse_log_level(level, "the tray").ok_or_else(|| format!("Unknown log level {}.", level))?;
+    log::info!("backend log level set to {}", level);
+    *app.state::<AppState>().log_level.lock().unwrap() = Some(level);
+    tray::refresh(app);
+    restart_backend_blocking(app).map_err(|e| e.to_string())
+}
+
+fn open_backend_log(app: &AppHandle) -> Option<BackendLog> {
+    match BackendLog::create(app) {
+        Ok(log) => Some(log),
+        Err(e) => {
+            log::warn!("backend output will not be captured: {}", e);
+            None
         }
     }
-    None
 }
 
-fn spawn_backend(port: u16, app: &AppHandle) -> Option<BackendChild> {
-    // 1) Try sidecar first (no Python required for end users)
-    if let Some(dist) = repo_frontend_dist_guess() {
-        // Make the viewer serve our built frontend at '/'
-        std::env::set_var("RUNICORN_FRONTEND_DIST", dist.to_string_lossy().as_ref());
+// Other Runicorn settings the backend reads from its environment.
+const FORWARDED_ENV: &[&str] = &["RUNICORN_DISABLE_MODERN_STORAGE", "RUNICORN_SSH_PATH", "RUNICORN_ON"];
+
+/// Storage folder the backend will use: the one picked in the app, else
+/// `RUNICORN_STORAGE_DIR` or `RUNICORN_DIR`. `None` leaves it to the backend's
+/// own config.
+fn effective_storage_dir(app: &AppHandle) -> Option<PathBuf> {
+    let state: tauri::State<AppState> = app.state();
+    let picked = state.storage_dir.lock().unwrap().clone();
+    picked.or_else(|| {
+        ["RUNICORN_STORAGE_DIR", "RUNICORN_DIR"]
+            .iter()
+            .find_map(|k| std::env::var_os(k).filter(|v| !v.is_empty()))
+            .map(PathBuf::from)
+    })
+}
+
+/// Env vars set explicitly on both spawn paths rather than relying on
+/// inheritance, which some launchers don't preserve.
+fn backend_env(app: &AppHandle) -> Vec<(&'static str, String)> {
+    let state: tauri::State<AppState> = app.state();
+    let mut env = vec![
+        (auth::ENV_KEY, auth::token().to_string()),
+        ("RUNICORN_LOG_LEVEL", backend_log_level(&state).to_string()),
+    ];
+    if let Some(csp) = navigation::content_security_policy(app) {
+        env.push(("RUNICORN_CSP", csp));
+    }
+    // Without an embedded frontend, make the viewer serve our built one at '/'
+    if !frontend::embedded(app) && frontend::dev_server().is_none() {
+        match frontend_dist(app) {
+            Some(dist) => env.push(("RUNICORN_FRONTEND_DIST", dist.to_string_lossy().into_owned())),
+            None => log::info!("no frontend dist, the backend serves its packaged UI"),
+        }
+    }
+    if let Some(dir) = effective_storage_dir(app) {
+        for key in storage::ENV_KEYS {
+            env.push((key, dir.to_string_lossy().into_owned()));
+        }
     }
-    if let Ok(cmd) = app.shell().sidecar("runicorn-viewer") {
-        if let Ok((_rx, child)) = cmd.args(["--host", "127.0.0.1", "--port", &port.to_string()]).spawn() {
-            return Some(BackendChild::Sidecar(child));
+    for key in FORWARDED_ENV {
+        if let Ok(val) = std::env::var(key) {
+            env.push((*key, val));
         }
     }
+    env
+}
 
-    // 2) Fallback: spawn python-based backend (dev-friendly)
-    let python = std::env::var("RUNICORN_DESKTOP_PY").unwrap_or_else(|_| "python".to_string());
-    let mut cmd = Command::new(python);
+fn spawn_python_backend(port: u16, app: &AppHandle) -> Result<BackendChild, SpawnError> {
+    let (python, (major, minor)) = python::find().map_err(SpawnError::PythonMissing)?;
+    log::info!("using Python {}.{} via `{}`", major, minor, python);
+    let mut cmd = python.command();
     cmd.args([
         "-X", "utf8",
         "-m", "uvicorn",
         "runicorn.viewer:create_app",
         "--factory",
-        "--host", "127.0.0.1",
+        "--host", &bind_host(),
         "--port", &port.to_string(),
+        "--log-level", backend_log_level(&app.state::<AppState>()),
     ])
-    .stdin(Stdio::null())
-    .stdout(Stdio::null())
-    .stderr(Stdio::null());
-    if let Some(src_dir) = repo_src_dir_guess() {
+    .args(extra_backend_args(app))
+    .envs(backend_env(app))
+    .stdin(Stdio::null());
+    let backend_log = open_backend_log(app);
+    if backend_log.is_some() {
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    } else {
+        cmd.stdout(Stdio::null()).stderr(Stdio::null());
+    }
+    if let Some(src_dir) = python_src_dir(app) {
         let py_path_key = "PYTHONPATH";
         let mut val = std::env::var(py_path_key).unwrap_or_default();
         if !val.is_empty() { if cfg!(target_os = "windows") { val.push(';') } else { val.push(':') } }
         val.push_str(&src_dir.to_string_lossy());
         cmd.env(py_path_key, val);
     }
-    if let Some(dist) = repo_frontend_dist_guess() {
-        cmd.env("RUNICORN_FRONTEND_DIST", dist.to_string_lossy().as_ref());
+    match cmd.spawn() {
+        Ok(mut child) => {
+            if let Some(backend_log) = backend_log {
+                backend_log.note(&format!("started python backend on port {}", port));
+                if let Some(out) = child.stdout.take() {
+                    backend_log.drain(out);
+                }
+                if let Some(err) = child.stderr.take() {
+                    backend_log.drain(err);
+                }
+            }
+            Ok(BackendChild::Python(child))
+        }
+        Err(e) => Err(SpawnError::Spawn { program: python.to_string(), source: e.to_string() }),
     }
-    cmd.spawn().ok().map(BackendChild::Python)
 }
 
-fn wait_ready(port: u16, timeout_secs: u64) -> bool {
-    let url = format!("http://127.0.0.1:{}/api/health", port);
-    let start = Instant::now();
-    while start.elapsed() < Duration::from_secs(timeout_secs) {
-        let resp = ureq::get(&url).timeout(Duration::from_secs(1)).call();
-        if resp.is_ok() { return true; }
-        thread::sleep(Duration::from_millis(300));
+/// `$APPDIR` of a running AppImage: the mount point binaries and resources live under.
+fn appimage_dir() -> Option<PathBuf> {
+    if cfg!(target_os = "linux") {
+        std::env::var_os("APPDIR").filter(|v| !v.is_empty()).map(PathBuf::from)
+    } else {
+        None
     }
-    false
 }
 
-#[tauri::command]
-fn get_backend_url(state: tauri::State<'_, AppState>) -> String {
-    state
-        .backend_url
-        .lock()
-        .unwrap()
-        .clone()
-        .unwrap_or_else(|| "http://127.0.0.1:8000".into())
+/// The first candidate `valid` accepts, logging which strategy found it.
+fn first_dir(what: &str, candidates: Vec<(&'static str, PathBuf)>, valid: impl Fn(&Path) -> bool) -> Option<PathBuf> {
+    let found = candidates.into_iter().find(|(_, dir)| valid(dir));
+    match &found {
+        Some((strategy, dir)) => log::info!("{}: {} (via {})", what, dir.display(), strategy),
+        None => log::info!("{}: not found", what),
+    }
+    found.map(|(_, dir)| dir)
 }
 
-enum BackendChild {
-    Sidecar(ShellChild),
-    Python(Child),
+fn exe_dir() -> Option<PathBuf> {
+    tauri::utils::platform::current_exe().ok()?.parent().map(Path::to_path_buf)
 }
 
-struct AppState {
-    child: Mutex<Option<BackendChild>>,
-    backend_url: Mutex<Option<String>>,
+/// The checkout a debug build was compiled from (three levels above
+/// src-tauri). Anchored at the manifest, not the launch directory.
+fn dev_checkout() -> Option<PathBuf> {
+    cfg!(debug_assertions).then(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("../../.."))
 }
 
-fn kill_child(state: &tauri::State<'_, AppState>) {
-    if let Some(child) = state.child.lock().unwrap().take() {
-        match child {
-            BackendChild::Sidecar(c) => {
-                let _ = c.kill();
+/// Built frontend for the backend to serve: `RUNICORN_FRONTEND_DIST` if set,
+/// then the bundled copy in the resource dir (or under `$APPDIR` in an
+/// AppImage) or next to the executable, with the dev checkout as a last resort.
+fn frontend_dist(app: &AppHandle) -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("RUNICORN_FRONTEND_DIST").filter(|v| !v.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    let mut candidates = Vec::new();
+    if let Ok(dir) = app.path().resource_dir() {
+        candidates.push(("resource dir", dir.join("frontend")));
+    }
+    if let Some(appdir) = appimage_dir() {
+        candidates.push(("$APPDIR", appdir.join("usr/lib").join(&app.package_info().name).join("frontend")));
+    }
+    if let Some(dir) = exe_dir() {
+        candidates.push(("executable dir", dir.join("frontend")));
+    }
+    if let Some(root) = dev_checkout() {
+        candidates.push(("dev checkout", root.join("web/frontend/dist")));
+    }
+    first_dir("frontend dist", candidates, |dir| dir.join("index.html").is_file())
+}
+
+fn os_assigned_port() -> Option<PortReservation> {
+    // Binding port 0 makes the kernel pick a free port.
+    bind_port(0).and_then(PortReservation::new)
+}
+
+const EPHEMERAL_PORTS: std::ops::RangeInclusive<u16> = 49152..=65535;
+const SCAN_THREADS: u16 = 8;
+
+/// Probes the ephemeral range on a few threads, each taking every
+/// SCAN_THREADS-th port, and keeps the lowest port that bound.
+fn scan_ephemeral_port() -> Option<PortReservation> {
+    let found = AtomicBool::new(false);
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..SCAN_THREADS)
+            .map(|offset| {
+                let found = &found;
+                scope.spawn(move || {
+                    let ports = EPHEMERAL_PORTS.skip(offset as usize).step_by(SCAN_THREADS as usize);
+                    for port in ports {
+                        if found.load(Ordering::Relaxed) {
+                            return None;
+                        }
+                        if let Some(listener) = bind_port(port) {
+                            found.store(true, Ordering::Relaxed);
+                            return PortReservation::new(listener);
+                        }
+                    }
+                    None
+                })
+            })
+            .collect();
+        // Losing candidates drop here, releasing their ports.
+        workers.into_iter().filter_map(|w| w.join().ok().flatten()).min_by_key(|r| r.port)
+    })
+}
+
+/// `RUNICORN_PORT_STRATEGY=scan` prefers scanning the ephemeral range over
+/// letting the OS assign a port.
+fn scan_port_strategy() -> bool {
+    std::env::var("RUNICORN_PORT_STRATEGY")
+        .map(|v| v.trim().eq_ignore_ascii_case("scan"))
+        .unwrap_or(false)
+}
+
+#[derive(Debug)]
+struct PortInUse {
+    port: u16,
+    source: &'static str,
+}
+
+impl fmt::Display for PortInUse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&i18n::tf("port.in_use", &[&self.port, &self.source]))
+    }
+}
+
+fn parse_port(raw: &str) -> Option<u16> {
+    match raw.trim().parse::<u16>() {
+        Ok(0) | Err(_) => None,
+        Ok(p) => Some(p),
+    }
+}
+
+fn env_port() -> Option<u16> {
+    let raw = std::env::var("RUNICORN_PORT").ok()?;
+    let port = parse_port(&raw);
+    if port.is_none() {
+        log::warn!("ignoring RUNICORN_PORT={:?}: expected a port number in 1..=65535", raw);
+    }
+    port
+}
+
+/// Parses `--port N` / `--port=N` from the command line.
+fn cli_port() -> Option<u16> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let raw = if arg == "--port" {
+            args.next().unwrap_or_default()
+        } else if let Some(v) = arg.strip_prefix("--port=") {
+            v.to_string()
+        } else {
+            continue;
+        };
+        let port = parse_port(&raw);
+        if port.is_none() {
+            log::warn!("ignoring --port {:?}: expected a port number in 1..=65535", raw);
+        }
+        return port;
+    }
+    None
+}
+
+const DEFAULT_PORT: u16 = 8000;
+
+/// Port pinned by `--port`, `RUNICORN_PORT`, the active profile or
+/// runicorn.toml (in that order), along with where it came from.
+fn pinned_port(
+    cli_port: Option<u16>,
+    profile_port: Option<u16>,
+    config_port: Option<u16>,
+) -> Option<(u16, &'static str)> {
+    cli_port
+        .map(|p| (p, "--port"))
+        .or_else(|| env_port().map(|p| (p, "RUNICORN_PORT")))
+        .or_else(|| profile_port.map(|p| (p, "the active profile")))
+        .or_else(|| config_port.filter(|p| *p != 0).map(|p| (p, config::CONFIG_FILE_NAME)))
+}
+
+fn pick_port(pinned: Option<(u16, &'static str)>) -> Result<PortReservation, PortInUse> {
+    // A pinned port is honored exactly; never silently move off it.
+    if let Some((port, source)) = pinned {
+        return bind_port(port).and_then(PortReservation::new).ok_or(PortInUse { port, source });
+    }
+    let preferred = DEFAULT_PORT;
+    if let Some(reservation) = bind_port(preferred).and_then(PortReservation::new) {
+        return Ok(reservation);
+    }
+    let fallback = if scan_port_strategy() {
+        scan_ephemeral_port().or_else(os_assigned_port)
+    } else {
+        os_assigned_port().or_else(scan_ephemeral_port)
+    };
+    fallback.ok_or(PortInUse { port: preferred, source: "the default (no other free port was found)" })
+}
+
+/// Directory added to `PYTHONPATH` so `runicorn` imports without installation:
+/// the bundled copy (`Resources/python`, shipped by tauri.macos.conf.json) or
+/// one next to the executable, else the dev checkout's `src`.
+fn python_src_dir(app: &AppHandle) -> Option<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Ok(dir) = app.path().resource_dir() {
+        candidates.push(("resource dir", dir.join("python")));
+    }
+    if let Some(dir) = exe_dir() {
+        candidates.push(("executable dir", dir.join("python")));
+    }
+    if let Some(root) = dev_checkout() {
+        candidates.push(("dev checkout", root.join("src")));
+    }
+    first_dir("python src dir", candidates, |dir| dir.join("runicorn").is_dir())
+}
+
+const SIDECAR_NAME: &str = "runicorn-viewer";
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).map(|m| m.permissions().mode() & 0o111 != 0).unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    true
+}
+
+/// Resolves the sidecar the way the shell plugin does, next to our own
+/// executable where Tauri puts `externalBin` (inside an AppImage also
+/// `$APPDIR/usr/bin`, in case the exe path was resolved outside the mount),
+/// and checks it can actually be run.
+fn resolve_sidecar() -> Result<PathBuf, String> {
+    let file = format!("{}{}", SIDECAR_NAME, std::env::consts::EXE_SUFFIX);
+    let mut candidates = Vec::new();
+    let exe = tauri::utils::platform::current_exe().map_err(|e| e.to_string())?;
+    if let Some(dir) = exe.parent() {
+        candidates.push(dir.join(&file));
+    }
+    if let Some(appdir) = appimage_dir() {
+        candidates.push(appdir.join("usr/bin").join(&file));
+    }
+    let Some(path) = candidates.iter().find(|p| p.is_file()) else {
+        let tried: Vec<String> = candidates.iter().map(|p| p.display().to_string()).collect();
+        return Err(format!("{} does not exist (looked in {})", file, tried.join(", ")));
+    };
+    if !is_executable(path) {
+        return Err(format!("{} is not executable", path.display()));
+    }
+    Ok(path.clone())
+}
+
+fn spawn_sidecar(port: u16, app: &AppHandle) -> Result<BackendChild, SpawnError> {
+    let path = resolve_sidecar().map_err(SpawnError::SidecarMissing)?;
+    log::info!("sidecar resolved to {}", path.display());
+    integrity::verify(&path).map_err(|reason| {
+        log::error!("refusing to run the sidecar: {}", reason);
+        SpawnError::SidecarTampered(reason)
+    })?;
+    let cmd = app.shell().command(&path);
+    let (mut rx, child) = cmd
+        .args(["--host", &bind_host(), "--port", &port.to_string()])
+        .args(["--log-level", backend_log_level(&app.state::<AppState>())])
+        .args(extra_backend_args(app))
+        .envs(backend_env(app))
+        .spawn()
+        .map_err(|e| SpawnError::Spawn { program: path.display().to_string(), source: e.to_string() })?;
+    let backend_log = open_backend_log(app);
+    if let Some(backend_log) = &backend_log {
+        backend_log.note(&format!("started sidecar backend on port {}", port));
+    }
+    // Drain the event channel for the log and to learn when the sidecar exits.
+    let exited = Arc::new(AtomicBool::new(false));
+    let exited_flag = exited.clone();
+    tauri::async_runtime::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            if let Some(backend_log) = &backend_log {
+                backend_log.sidecar_event(&event);
             }
-            BackendChild::Python(mut c) => {
-                let _ = c.kill();
-                let _ = c.wait();
+            if let CommandEvent::Terminated(_) = event {
+                break;
             }
         }
-    }
+        exited_flag.store(true, Ordering::SeqCst);
+    });
+    Ok(BackendChild::Sidecar(SidecarChild { child, exited }))
 }
 
-fn start(app: AppHandle) {
-    let port = pick_port();
-    // First attempt: sidecar (preferred for end users)
-    let mut child = spawn_backend(port, &app).expect("failed to spawn backend (sidecar/python)");
+/// Which backend programs may be started, from `RUNICORN_BACKEND`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BackendMode {
+    /// The sidecar, falling back to Python when it is missing or never gets ready.
+    Auto,
+    /// Only the sidecar; its failures are errors.
+    Sidecar,
+    /// Only Python, e.g. so local code edits take effect.
+    Python,
+}
+
+/// `RUNICORN_BACKEND` (`auto`, `sidecar` or `python`), else `python` when
+/// runicorn.toml sets `prefer_sidecar = false`, else `auto`.
+fn backend_mode(config: &config::Config) -> BackendMode {
+    let fallback = if config.prefer_sidecar() { BackendMode::Auto } else { BackendMode::Python };
+    match std::env::var("RUNICORN_BACKEND").map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+        Ok("auto") => BackendMode::Auto,
+        Ok("sidecar") => BackendMode::Sidecar,
+        Ok("python") => BackendMode::Python,
+        Ok("") | Err(_) => fallback,
+        Ok(other) => {
+            log::warn!("ignoring RUNICORN_BACKEND={:?}; expected auto, sidecar or python", other);
+            fallback
+        }
+    }
+}
 
+fn spawn_backend(port: u16, app: &AppHandle) -> Result<BackendChild, SpawnError> {
     let state: tauri::State<AppState> = app.state();
-    *state.child.lock().unwrap() = Some(child);
+    let mode = backend_mode(&state.config);
+    log::info!("backend mode: {:?}", mode);
+    match mode {
+        BackendMode::Sidecar => spawn_sidecar(port, app),
+        BackendMode::Python => spawn_python_backend(port, app),
+        // Sidecar first (no Python required for end users), then Python (dev-friendly).
+        // A tampered sidecar is an error, not a reason to quietly run something else.
+        BackendMode::Auto => spawn_sidecar(port, app).or_else(|e| match e {
+            SpawnError::SidecarTampered(_) => Err(e),
+            e => {
+                log::warn!("sidecar unavailable, falling back to Python: {}", e);
+                spawn_python_backend(port, app)
+            }
+        }),
+    }
+}
 
-    if !wait_ready(port, 30) {
-        // Fallback: kill current child and try python-based backend
-        kill_child(&state);
-        if let Some(py_child) = spawn_python_backend(port) {
-            *state.child.lock().unwrap() = Some(py_child);
-            let _ = wait_ready(port, 30);
+const DEFAULT_HEALTH_PATH: &str = "/api/health";
+static HEALTH_PATH: OnceLock<String> = OnceLock::new();
+
+/// Picks the health check path once at startup: `RUNICORN_HEALTH_PATH`, else
+/// `health_path` in runicorn.toml, else `/api/health`. A path not starting
+/// with `/` is ignored with a warning.
+fn init_health_path(config: &config::Config) {
+    let chosen = [
+        ("RUNICORN_HEALTH_PATH", std::env::var("RUNICORN_HEALTH_PATH").ok()),
+        (config::CONFIG_FILE_NAME, config.health_path.clone()),
+    ]
+    .into_iter()
+    .filter_map(|(source, path)| Some((source, path?.trim().to_string())))
+    .filter(|(_, path)| !path.is_empty())
+    .find(|(source, path)| {
+        let valid = path.starts_with('/');
+        if !valid {
+            log::warn!("ignoring health path {:?} from {}: it must start with /", path, source);
         }
+        valid
+    });
+    if let Some((source, path)) = &chosen {
+        log::info!("health checks use {} (from {})", path, source);
     }
-    let url = format!("http://127.0.0.1:{}/", port);
-    *state.backend_url.lock().unwrap() = Some(url.clone());
+    let _ = HEALTH_PATH.set(chosen.map(|(_, path)| path).unwrap_or_else(|| DEFAULT_HEALTH_PATH.to_string()));
+}
 
-    WebviewWindowBuilder::new(&app, "main", WebviewUrl::External(url.parse().unwrap()))
-        .title("Runicorn")
-        .resizable(true)
-        .build()
-        .expect("failed to create window");
+fn health_path() -> &'static str {
+    HEALTH_PATH.get().map(String::as_str).unwrap_or(DEFAULT_HEALTH_PATH)
 }
 
-fn main() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_shell::init())
-        .manage(AppState { child: Mutex::new(None), backend_url: Mutex::new(None) })
-        .setup(|app| {
-            // spawn backend in a background thread to avoid blocking
-            let handle = app.handle().clone();
-            thread::spawn(move || start(handle));
-            Ok(())
-        })
-        .on_window_event(|window, event| {
-            if let WindowEvent::CloseRequested { .. } = event {
+fn health_url(port: u16) -> String {
+    format!("http://{}:{}{}", connect_host(), port, health_path())
+}
+
+fn health_url_for(base_url: &str) -> String {
+    format!("{}{}", base_url.trim_end_matches('/'), health_path())
+}
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// What answered a health probe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Health {
+    Healthy,
+    /// An error status, e.g. a 503 while the backend warms up.
+    Status(u16),
+    /// A success whose body isn't Runicorn's: another program has the port.
+    Foreign,
+    /// Nothing answered.
+    Unreachable,
+}
+
+/// Whether a health reply came from Runicorn: `service: "runicorn"`, or for
+/// backends predating that field, `status: "ok"` plus a `version`.
+fn is_runicorn_health(reply: ureq::Response) -> bool {
+    let Ok(body) = reply.into_json::<serde_json::Value>() else { return false };
+    body["service"] == "runicorn" || (body["status"] == "ok" && body["version"].is_string())
+}
+
+/// Whether a 401 came from another desktop instance's backend, which only
+/// answers requests carrying that instance's own token.
+fn is_runicorn_auth_challenge(reply: ureq::Response) -> bool {
+    let Ok(body) = reply.into_json::<serde_json::Value>() else { return false };
+    body["service"] == "runicorn" || body["detail"].as_str().is_some_and(|d| d.contains("Runicorn auth token"))
+}
+
+/// One-shot health check, shared by the readiness loop and the attach probe.
+/// Redirects are followed (ureq's default of up to 5), so a redirected health
+/// URL still counts.
+fn check_health(port: u16, timeout: Duration) -> Health {
+    match auth::get(&health_url(port)).timeout(timeout).call() {
+        // Decided in the arm, not a guard: reading the body consumes the reply.
+        Ok(reply) => if is_runicorn_health(reply) { Health::Healthy } else { Health::Foreign },
+        Err(ureq::Error::Status(code, _)) => Health::Status(code),
+        Err(_) => Health::Unreachable,
+    }
+}
+
+/// Cheap `HEAD` probe for the polling loop. `Healthy` here only means
+/// something answered; confirm it with [`check_health`]. A 405 from a backend
+/// whose health route predates `HEAD` support counts as answering.
+fn check_reachable(port: u16, timeout: Duration) -> Health {
+    match auth::request("HEAD", &health_url(port)).timeout(timeout).call() {
+        Ok(_) | Err(ureq::Error::Status(405, _)) => Health::Healthy,
+        Err(ureq::Error::Status(code, _)) => Health::Status(code),
+        Err(_) => Health::Unreachable,
+    }
+}
+
+/// Outcome of waiting for the backend to come up.
+#[derive(Debug, Clone, Copy)]
+enum Readiness {
+    Ready { elapsed: Duration },
+    /// Nothing answered the health endpoint before the deadline.
+    Timeout { waited: Duration },
+    /// The backend answered, but its last reply was an error status.
+    HttpError(u16),
+    /// The process died while nothing was answering; no point waiting on.
+    Exited { after: Duration },
+    /// Something other than Runicorn answered on the port.
+    Foreign,
+    /// The app started shutting down while we were waiting.
+    Cancelled,
+}
+
+impl Readiness {
+    fn is_ready(&self) -> bool {
+        matches!(self, Readiness::Ready { .. })
+    }
+}
+
+impl fmt::Display for Readiness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Readiness::Ready { elapsed } => i18n::tf("ready.ready", &[&format!("{:.1?}", elapsed)]),
+            Readiness::Timeout { waited } => i18n::tf("ready.timeout", &[&waited.as_secs()]),
+            Readiness::HttpError(code) => i18n::tf("ready.http_error", &[code]),
+            Readiness::Exited { after } => i18n::tf("ready.exited", &[&format!("{:.1?}", after)]),
+            Readiness::Foreign => i18n::t("ready.foreign").to_string(),
+            Readiness::Cancelled => i18n::t("ready.cancelled").to_string(),
+        };
+        f.write_str(&text)
+    }
+}
+
+/// Polls the health endpoint until it answers or `timeout_secs` of wall-clock
+/// time have passed. Request timeouts and sleeps are clipped to the deadline,
+/// so the wait never overshoots it. `on_attempt` runs before every probe with
+/// the 1-based attempt number. Setting `cancel` ends the wait at the next poll.
+///
+/// Error statuses (e.g. a 503 while uvicorn warms up) mean "still starting"
+/// and keep the loop going; a refused connection once `exited` reports the
+/// process gone ends it right away.
+fn wait_ready(
+    port: u16,
+    timeout_secs: u64,
+    cancel: &AtomicBool,
+    mut exited: impl FnMut() -> bool,
+    mut on_attempt: impl FnMut(u32),
+) -> Readiness {
+    let start = Instant::now();
+    let deadline = start + Duration::from_secs(timeout_secs);
+    let mut last_status = None;
+    let mut attempt = 0;
+    loop {
+        if cancel.load(Ordering::SeqCst) {
+            return Readiness::Cancelled;
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return match last_status {
+                Some(code) => Readiness::HttpError(code),
+                None => Readiness::Timeout { waited: start.elapsed() },
+            };
+        }
+        attempt += 1;
+        on_attempt(attempt);
+        // Poll with HEAD; only a backend that answers gets the GET that checks its body.
+        let timeout = remaining.min(PROBE_TIMEOUT);
+        let health = match check_reachable(port, timeout) {
+            Health::Healthy => check_health(port, timeout),
+            other => other,
+        };
+        match health {
+            Health::Healthy => return Readiness::Ready { elapsed: start.elapsed() },
+            Health::Foreign => return Readiness::Foreign,
+            Health::Status(code) => last_status = Some(code),
+            Health::Unreachable if exited() => return Readiness::Exited { after: start.elapsed() },
+            Health::Unreachable => {}
+        }
+        thread::sleep(POLL_INTERVAL.min(deadline.saturating_duration_since(Instant::now())));
+    }
+}
+
+const DEFAULT_STARTUP_TIMEOUT_SECS: u64 = 30;
+
+/// Readiness timeout from `RUNICORN_STARTUP_TIMEOUT`, else `configured`, clamped
+/// to 3..=120 seconds.
+fn startup_timeout_secs(configured: Option<u64>) -> u64 {
+    let fallback = configured.unwrap_or(DEFAULT_STARTUP_TIMEOUT_SECS).clamp(3, 120);
+    match std::env::var("RUNICORN_STARTUP_TIMEOUT") {
+        Ok(raw) => match raw.trim().parse::<u64>() {
+            Ok(secs) => secs.clamp(3, 120),
+            Err(_) => {
+                log::warn!("ignoring RUNICORN_STARTUP_TIMEOUT={:?}: expected whole seconds", raw);
+                fallback
+            }
+        },
+        Err(_) => fallback,
+    }
+}
+
+fn attach_enabled() -> bool {
+    !matches!(std::env::var("RUNICORN_ATTACH").as_deref(), Ok("0") | Ok("false"))
+}
+
+/// Thin-client mode: never spawn or pick a port, only connect to a backend
+/// someone else runs. `RUNICORN_NO_SPAWN=1`, else `spawn_backend = false`.
+fn no_spawn(config: &config::Config) -> bool {
+    match std::env::var("RUNICORN_NO_SPAWN").as_deref() {
+        Ok("1") | Ok("true") => true,
+        Ok("0") | Ok("false") => false,
+        _ => !config.spawn_backend(),
+    }
+}
+
+/// The backend to connect to in thin-client mode: `RUNICORN_BACKEND_URL`,
+/// else `backend_url` from runicorn.toml. Invalid values are ignored.
+fn configured_backend_url(config: &config::Config) -> Option<String> {
+    if !no_spawn(config) {
+        return None;
+    }
+    let (source, raw) = match std::env::var("RUNICORN_BACKEND_URL") {
+        Ok(raw) if !raw.trim().is_empty() => ("RUNICORN_BACKEND_URL", raw),
+        _ => (config::CONFIG_FILE_NAME, config.backend_url.clone().filter(|u| !u.trim().is_empty())?),
+    };
+    match remote::validate(&raw) {
+        Ok(url) => {
+            log::info!("connecting to the backend at {} (from {}) instead of starting one", url, source);
+            Some(url)
+        }
+        Err(e) => {
+            log::warn!("ignoring backend URL from {}: {}", source, e);
+            None
+        }
+    }
+}
+
+/// The backend URL once it is healthy, `None` (null) while it is still
+/// starting or restarting.
+#[tauri::command]
+fn get_backend_url(state: tauri::State<'_, AppState>) -> Option<String> {
+    state.backend_url.lock().unwrap().clone()
+}
+
+/// The backend URL and token for a frontend loaded from the Vite dev server,
+/// which can't use the embedded protocol's `/api` proxy.
+#[tauri::command]
+fn get_backend_info(app: AppHandle) -> Option<serde_json::Value> {
+    frontend::backend_info(&app)
+}
+
+/// "Runicorn" alone, or "Runicorn — <detail>" when there is something to show.
+fn window_title(detail: Option<&str>) -> String {
+    match detail.map(str::trim).filter(|d| !d.is_empty()) {
+        Some(detail) => format!("Runicorn — {}", detail),
+        None => "Runicorn".to_string(),
+    }
+}
+
+/// Lets the frontend label the calling window with the run being viewed; an
+/// empty title resets it to plain "Runicorn".
+#[tauri::command]
+fn set_window_title(window: tauri::WebviewWindow, title: String) -> Result<(), String> {
+    window.set_title(&window_title(Some(&title))).map_err(|e| e.to_string())
+}
+
+/// Floats `window` above other apps, e.g. to watch a run next to an editor.
+/// The main window's choice is remembered across launches.
+fn apply_always_on_top(window: &tauri::WebviewWindow, enabled: bool) -> Result<(), String> {
+    window.set_always_on_top(enabled).map_err(|e| e.to_string())?;
+    if window.label() == MAIN_WINDOW {
+        let webview: &tauri::Webview = window.as_ref();
+        window_state::save_always_on_top(&webview.window(), enabled);
+        tray::refresh(window.app_handle());
+    }
+    Ok(())
+}
+
+/// Keeps the calling window above others (or stops doing so).
+#[tauri::command]
+fn set_always_on_top(window: tauri::WebviewWindow, enabled: bool) -> Result<(), String> {
+    apply_always_on_top(&window, enabled)
+}
+
+fn theme_label(theme: tauri::Theme) -> &'static str {
+    match theme {
+        tauri::Theme::Dark => "dark",
+        _ => "light",
+    }
+}
+
+/// `light` or `dark`: the storage folder's theme override if one is set,
+/// else the OS appearance.
+#[tauri::command]
+fn get_theme(window: tauri::WebviewWindow) -> Result<&'static str, String> {
+    window.theme().map(theme_label).map_err(|e| e.to_string())
+}
+
+/// Overrides the window theme (`light` or `dark`, `null` to follow the OS)
+/// and remembers it in the storage folder's workspace.
+#[tauri::command]
+fn set_theme(window: tauri::WebviewWindow, theme: Option<String>) -> Result<(), String> {
+    let parsed = match theme.as_deref() {
+        Some(raw) => Some(workspace::parse_theme(raw).ok_or_else(|| format!("unknown theme {}", raw))?),
+        None => None,
+    };
+    window.set_theme(parsed).map_err(|e| e.to_string())?;
+    if let Some(dir) = effective_storage_dir(window.app_handle()) {
+        workspace::save_theme(window.app_handle(), &dir, theme.as_deref());
+    }
+    Ok(())
+}
+
+const ZOOM_RANGE: std::ops::RangeInclusive<f64> = 0.3..=3.0;
+
+/// Zooms `window`'s content, clamped to a usable range, and remembers the
+/// factor for that window. Returns the factor applied.
+fn apply_zoom(window: &tauri::WebviewWindow, factor: f64) -> Result<f64, String> {
+    if !factor.is_finite() {
+        return Err(format!("invalid zoom factor {}", factor));
+    }
+    // Round away float drift from repeated steps.
+    let factor = (factor.clamp(*ZOOM_RANGE.start(), *ZOOM_RANGE.end()) * 100.0).round() / 100.0;
+    window.set_zoom(factor).map_err(|e| e.to_string())?;
+    let webview: &tauri::Webview = window.as_ref();
+    window_state::save_zoom(&webview.window(), factor);
+    Ok(factor)
+}
+
+/// Sets the calling window's zoom (1.0 is 100%). Returns the factor applied.
+#[tauri::command]
+fn set_zoom(window: tauri::WebviewWindow, factor: f64) -> Result<f64, String> {
+    apply_zoom(&window, factor)
+}
+
+#[tauri::command]
+fn reset_zoom(window: tauri::WebviewWindow) -> Result<f64, String> {
+    apply_zoom(&window, 1.0)
+}
+
+static COMPARE_WINDOW_SEQ: AtomicU32 = AtomicU32::new(1);
+
+/// `<backend>/runs/<id>`, with the id percent-encoded as a single path segment.
+fn run_url(base: &str, run_id: &str) -> Result<tauri::Url, String> {
+    let mut url: tauri::Url = base.parse().map_err(|e| format!("invalid backend url {}: {}", base, e))?;
+    url.path_segments_mut()
+        .map_err(|_| format!("invalid backend url {}", base))?
+        .pop_if_empty()
+        .push("runs")
+        .push(run_id);
+    Ok(url)
+}
+
+/// Where a window shows `backend` (optionally on one run): the Vite dev server
+/// or the embedded frontend when there is one, else the backend's own pages
+/// with the token.
+fn window_url(app: &AppHandle, backend: &str, run_id: Option<&str>) -> Result<tauri::Url, String> {
+    if let Some(dev) = frontend::dev_server() {
+        return match run_id {
+            Some(id) => run_url(dev.as_str(), id),
+            None => Ok(dev.clone()),
+        };
+    }
+    if frontend::embedded(app) {
+        let origin = frontend::origin();
+        return match run_id {
+            Some(id) => run_url(origin.as_str(), id),
+            None => Ok(origin),
+        };
+    }
+    let mut url = match run_id {
+        Some(id) => run_url(backend, id)?,
+        None => backend.parse().map_err(|e| format!("invalid backend url {}: {}", backend, e))?,
+    };
+    auth::add_to_url(&mut url);
+    Ok(url)
+}
+
+/// `RUNICORN_DEVTOOLS=1`: open the webview devtools with the main window,
+/// also in release builds.
+fn devtools_requested() -> bool {
+    matches!(std::env::var("RUNICORN_DEVTOOLS").as_deref(), Ok("1") | Ok("true"))
+}
+
+/// Devtools are always available in debug builds and only on request in release ones.
+fn devtools_enabled() -> bool {
+    cfg!(debug_assertions) || devtools_requested()
+}
+
+/// Hard refresh: sends `window` back to the start page on the current
+/// backend, without restarting it. Webviews in release builds often have no
+/// reload of their own.
+fn reload_window(window: &tauri::WebviewWindow) -> Result<(), String> {
+    let app = window.app_handle();
+    let backend = app.state::<AppState>().backend_url.lock().unwrap().clone();
+    let Some(backend) = backend else { return Err("The backend is not running yet.".to_string()) };
+    log::info!("reloading window {}", window.label());
+    window.navigate(window_url(app, &backend, None)?).map_err(|e| e.to_string())
+}
+
+/// Reloads the calling window onto the backend's start page.
+#[tauri::command]
+fn reload_app(window: tauri::WebviewWindow) -> Result<(), String> {
+    reload_window(&window)
+}
+
+/// Opens another window on the running backend, e.g. to compare two runs side
+/// by side. All windows share the one backend process.
+#[tauri::command]
+async fn open_compare_window(app: AppHandle, run_id: Option<String>) -> Result<String, String> {
+    let base = app
+        .state::<AppState>()
+        .backend_url
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("the backend is not ready yet")?;
+    let url = window_url(&app, &base, run_id.as_deref())?;
+    let label = format!("compare-{}", COMPARE_WINDOW_SEQ.fetch_add(1, Ordering::Relaxed));
+    let title = window_title(run_id.as_deref());
+    let nav_app = app.clone();
+    let nav_label = label.clone();
+    let builder = WebviewWindowBuilder::new(&app, &label, WebviewUrl::External(url))
+        .title(title)
+        .resizable(true)
+        .devtools(devtools_enabled())
+        .on_navigation(move |url| navigation::allow(&nav_app, &nav_label, url));
+    let window = tls::prepare(frontend::prepare(with_backend_global(&app, builder)))
+        .build()
+        .map_err(|e| e.to_string())?;
+    tls::apply(&window);
+    Ok(label)
+}
+
+struct SidecarChild {
+    child: ShellChild,
+    /// Set once the sidecar's event channel reports termination.
+    exited: Arc<AtomicBool>,
+}
+
+enum BackendChild {
+    Sidecar(SidecarChild),
+    Python(Child),
+}
+
+impl BackendChild {
+    fn pid(&self) -> u32 {
+        match self {
+            BackendChild::Sidecar(s) => s.child.pid(),
+            BackendChild::Python(c) => c.id(),
+        }
+    }
+
+    /// True once the process is gone, without blocking.
+    fn has_exited(&mut self) -> bool {
+        match self {
+            BackendChild::Sidecar(s) => s.exited.load(Ordering::SeqCst),
+            BackendChild::Python(c) => matches!(c.try_wait(), Ok(Some(_))),
+        }
+    }
+
+    fn kind(&self) -> BackendKind {
+        match self {
+            BackendChild::Sidecar(_) => BackendKind::Sidecar,
+            BackendChild::Python(_) => BackendKind::Python,
+        }
+    }
+}
+
+/// Owns a spawned backend and stops it when dropped, so it is cleaned up on
+/// every way out: `kill_child`, a panic unwinding past it, or the app state
+/// being torn down.
+struct BackendGuard(Option<BackendChild>);
+
+impl BackendGuard {
+    fn new(child: BackendChild) -> Self {
+        BackendGuard(Some(child))
+    }
+
+    /// Stops the backend now, with the usual grace period.
+    fn stop(mut self) {
+        if let Some(child) = self.0.take() {
+            terminate_backend(child);
+        }
+    }
+}
+
+impl std::ops::Deref for BackendGuard {
+    type Target = BackendChild;
+
+    fn deref(&self) -> &BackendChild {
+        self.0.as_ref().expect("a guard is only emptied when it stops")
+    }
+}
+
+impl std::ops::DerefMut for BackendGuard {
+    fn deref_mut(&mut self) -> &mut BackendChild {
+        self.0.as_mut().expect("a guard is only emptied when it stops")
+    }
+}
+
+impl Drop for BackendGuard {
+    fn drop(&mut self) {
+        if let Some(child) = self.0.take() {
+            log::warn!("backend guard dropped with the backend still running, stopping it");
+            terminate_backend(child);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum BackendKind {
+    Sidecar,
+    Python,
+    /// A backend we found already running and did not spawn.
+    Attached,
+    /// A backend elsewhere that `connect_remote` pointed us at.
+    Remote,
+}
+
+impl BackendKind {
+    fn label(self) -> &'static str {
+        match self {
+            BackendKind::Sidecar => "sidecar",
+            BackendKind::Python => "python",
+            BackendKind::Attached => "attached",
+            BackendKind::Remote => "remote",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct BackendStatus {
+    url: Option<String>,
+    healthy: bool,
+    pid: Option<u32>,
+    kind: Option<BackendKind>,
+}
+
+/// Pid and kind of the current backend; no kind while none is up.
+fn backend_identity(state: &AppState) -> (Option<u32>, Option<BackendKind>) {
+    let up = state.backend_url.lock().unwrap().is_some();
+    match state.child.lock().unwrap().as_ref() {
+        Some(child) => (Some(child.pid()), Some(child.kind())),
+        None if !up => (None, None),
+        None if state.remote.lock().unwrap().is_some() => (None, Some(BackendKind::Remote)),
+        None => (None, Some(BackendKind::Attached)),
+    }
+}
+
+fn backend_status(state: &AppState) -> BackendStatus {
+    let url = state.backend_url.lock().unwrap().clone();
+    let (pid, kind) = backend_identity(state);
+    let healthy = url
+        .as_deref()
+        .map(|u| auth::get(&health_url_for(u)).timeout(PROBE_TIMEOUT).call().is_ok())
+        .unwrap_or(false);
+    BackendStatus { url, healthy, pid, kind }
+}
+
+/// Support report: versions, resolved paths, backend state and recent log lines.
+#[tauri::command]
+async fn collect_diagnostics(app: AppHandle) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || diagnostics::collect(&app))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// OS, architecture and OS version, plus how the current backend runs.
+#[tauri::command]
+async fn get_platform_info(app: AppHandle) -> Result<platform::PlatformInfo, String> {
+    // The first OS version lookup may run a helper program.
+    tauri::async_runtime::spawn_blocking(move || {
+        let (_, kind) = backend_identity(&app.state::<AppState>());
+        platform::info(kind.map(BackendKind::label))
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// CPU and memory of the backend we spawned; `None` (null) for an attached
+/// or remote backend, whose process we don't track, or while none is up.
+#[tauri::command]
+async fn get_backend_resources(app: AppHandle) -> Result<Option<resources::BackendResources>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let pid = app.state::<AppState>().child.lock().unwrap().as_ref().map(|child| child.pid())?;
+        resources::sample(pid)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// App version plus the commit and date it was built from.
+#[tauri::command]
+fn get_app_version(app: AppHandle) -> about::AppVersion {
+    about::app_version(&app)
+}
+
+/// What is running right now: URL, a fresh health check, the OS pid of a
+/// process we spawned, and how it was started.
+#[tauri::command]
+async fn get_backend_status(app: AppHandle) -> Result<BackendStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || backend_status(&app.state::<AppState>()))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Lifecycle of the backend as the UI should present it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", content = "reason", rename_all = "lowercase")]
+enum BackendState {
+    Starting,
+    Ready,
+    Failed(String),
+    Stopped,
+}
+
+/// Label of the window whose close quits the app; comparison windows just close.
+const MAIN_WINDOW: &str = "main";
+
+struct AppState {
+    child: Mutex<Option<BackendGuard>>,
+    backend_url: Mutex<Option<String>>,
+    backend_state: Mutex<BackendState>,
+    requested_port: Option<u16>,
+    /// Settings from runicorn.toml, read once at startup.
+    config: config::Config,
+    /// Storage folder picked with `set_storage_dir`; `None` leaves it to the backend.
+    storage_dir: Mutex<Option<PathBuf>>,
+    /// Remote backend URL from `connect_remote`; `None` means local mode.
+    remote: Mutex<Option<String>>,
+    /// Port preference of the active local profile.
+    profile_port: Mutex<Option<u16>>,
+    /// `runicorn-desktop.pid` in the app config dir; `None` if that dir can't be resolved.
+    pidfile: Option<PathBuf>,
+    /// Serializes startup and restarts so two boots never race for the port.
+    boot_lock: Mutex<()>,
+    /// Set once the app is closing; an in-progress boot stops at its next poll.
+    shutting_down: AtomicBool,
+    /// Backend log level picked from the tray; `None` defers to the settings.
+    log_level: Mutex<Option<&'static str>>,
+    /// Set when the user chose the Runicorn backend already on the default
+    /// port; boots attach to it even when attaching is otherwise off.
+    attach_default: AtomicBool,
+}
+
+/// Asks the backend to exit, giving it a grace period to flush and close its
+/// files before force-killing it.
+fn terminate_backend(child: BackendChild) {
+    let grace = process::shutdown_grace();
+    match child {
+        BackendChild::Sidecar(SidecarChild { child, exited }) => {
+            let asked = process::request_terminate(child.pid());
+            if !asked || !process::wait_exit(|| exited.load(Ordering::SeqCst), grace) {
+                log::warn!("sidecar did not exit within {:?}, killing it", grace);
+                #[cfg(target_os = "windows")]
+                process::kill_tree(child.pid());
+                let _ = child.kill();
+            }
+        }
+        BackendChild::Python(mut c) => {
+            let asked = process::request_terminate(c.id());
+            if !asked || !process::wait_exit(|| matches!(c.try_wait(), Ok(Some(_))), grace) {
+                log::warn!("python backend did not exit within {:?}, killing it", grace);
+                #[cfg(target_os = "windows")]
+                process::kill_tree(c.id());
+                let _ = c.kill();
+            }
+            let _ = c.wait();
+        }
+    }
+}
+
+fn kill_child(state: &tauri::State<'_, AppState>) {
+    if let Some(child) = state.child.lock().unwrap().take() {
+        child.stop();
+        if let Some(path) = &state.pidfile {
+            pidfile::remove(path);
+        }
+    }
+}
+
+/// Stores a freshly spawned backend and records it in the pidfile.
+fn set_child(state: &AppState, child: BackendChild, port: u16) {
+    *state.child.lock().unwrap() = Some(BackendGuard::new(child));
+    record_backend(state, &format!("http://{}:{}/", connect_host(), port));
+}
+
+/// Writes the current backend to the pidfile, for external tools and for
+/// reaping it after a crash.
+fn record_backend(state: &AppState, url: &str) {
+    let Some(path) = &state.pidfile else { return };
+    let (pid, kind) = backend_identity(state);
+    let port = url.parse::<tauri::Url>().ok().and_then(|u| u.port_or_known_default()).unwrap_or_default();
+    pidfile::write(path, pid, port, kind.map(BackendKind::label).unwrap_or_default(), url);
+}
+
+fn show_error(app: &AppHandle, message: &str) {
+    app.dialog()
+        .message(message)
+        .title(i18n::t("dialog.title"))
+        .kind(MessageDialogKind::Error)
+        .blocking_show();
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Encodes a self-contained page as a `data:` URL so it loads without a backend.
+fn data_url(html: &str) -> tauri::Url {
+    let mut url = String::from("data:text/html;charset=utf-8,");
+    for b in html.bytes() {
+        if b.is_ascii_alphanumeric() || b"-_.~".contains(&b) {
+            url.push(b as char);
+        } else {
+            url.push_str(&format!("%{:02X}", b));
+        }
+    }
+    let url = url.parse().expect("data url is always valid");
+    navigation::register_own_page(&url);
+    url
+}
+
+fn error_page_url(message: &str) -> tauri::Url {
+    data_url(&i18n::localize(include_str!("error.html")).replace("{{message}}", &html_escape(message)))
+}
+
+/// Reports a startup failure: in the splash's error view (with Retry) when it is
+/// up, otherwise as a dialog plus an error page in the main window.
+fn report_startup_failure(app: &AppHandle, message: &str) {
+    if !splash::show_error(app, message) {
+        show_error(app, message);
+        open_error_window(app, message);
+    }
+}
+
+fn open_error_window(app: &AppHandle, message: &str) {
+    let builder = WebviewWindowBuilder::new(app, MAIN_WINDOW, WebviewUrl::External(error_page_url(message)))
+        .title("Runicorn")
+        .resizable(true)
+        .devtools(devtools_enabled());
+    let built = tls::prepare(builder).build();
+    if let Err(e) = built {
+        log::error!("failed to create error window: {}", e);
+    }
+}
+
+/// Asks for a backend URL in the main window (thin-client mode with none
+/// configured), prefilled with `url` and showing `message` from a failed try.
+fn open_connect_page(app: &AppHandle, url: &str, message: &str) {
+    let page = data_url(
+        &i18n::localize(include_str!("connect.html"))
+            .replace("{{url}}", &html_escape(url))
+            .replace("{{message}}", &html_escape(message)),
+    );
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        let _ = window.navigate(page);
+        show_main_window(app);
+        return;
+    }
+    let nav_app = app.clone();
+    let builder = WebviewWindowBuilder::new(app, MAIN_WINDOW, WebviewUrl::External(page))
+        .title("Runicorn")
+        .resizable(true)
+        .devtools(devtools_enabled())
+        .on_navigation(move |url| navigation::allow(&nav_app, MAIN_WINDOW, url));
+    if let Err(e) = tls::prepare(builder).build() {
+        log::error!("failed to create the connect window: {}", e);
+    }
+}
+
+/// The first-run dead end: no sidecar and no usable Python. Shows the steps
+/// out in the main window, with Retry, Copy Diagnostics and a connect field.
+fn open_no_backend_page(app: &AppHandle, message: &str) {
+    let min_python = format!("{}.{}", python::MIN_VERSION.0, python::MIN_VERSION.1);
+    let page = data_url(
+        &i18n::localize(include_str!("no_backend.html"))
+            .replace("{{min_python}}", &min_python)
+            .replace("{{message}}", &html_escape(message)),
+    );
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        let _ = window.navigate(page);
+        show_main_window(app);
+        return;
+    }
+    let nav_app = app.clone();
+    let builder = WebviewWindowBuilder::new(app, MAIN_WINDOW, WebviewUrl::External(page))
+        .title("Runicorn")
+        .inner_size(720.0, 640.0)
+        .resizable(true)
+        .devtools(devtools_enabled())
+        .on_navigation(move |url| navigation::allow(&nav_app, MAIN_WINDOW, url));
+    if let Err(e) = tls::prepare(builder).build() {
+        log::error!("failed to create the no-backend window: {}", e);
+        show_error(app, message);
+    }
+}
+
+/// Copy Diagnostics on the no-backend page: the same report as the tray's.
+fn copy_diagnostics_from_page(app: &AppHandle) {
+    let report = diagnostics::collect(app);
+    match app.clipboard().write_text(report) {
+        Ok(()) => {
+            if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+                let _ = window.eval("copied()");
+            }
+        }
+        Err(e) => show_error(app, &i18n::tf("tray.copy_diagnostics_failed", &[&e])),
+    }
+}
+
+/// Connects to the URL entered on the connect page: checks that a backend
+/// answers there, remembers it like `connect_remote`, then starts up against
+/// it. On failure the page comes back with the reason.
+fn connect_entered_url(app: &AppHandle, raw: &str) {
+    let checked = remote::validate(raw).and_then(|url| {
+        remote::probe(&url, None, false).map_err(|reason| format!("No Runicorn backend answered at {}: {}", url, reason))?;
+        remote::save(app, Some((&url, None, false))).map_err(|e| format!("Failed to save the backend URL: {}", e))?;
+        Ok(url)
+    });
+    match checked {
+        Ok(url) => {
+            log::info!("connecting to the entered backend {}", url);
+            auth::use_remote(None);
+            tls::set_remote(Some(&url), false);
+            *app.state::<AppState>().remote.lock().unwrap() = Some(url);
+            spawn_start(app.clone());
+        }
+        Err(e) => {
+            log::warn!("{}", e);
+            open_connect_page(app, raw, &e);
+        }
+    }
+}
+
+// Startup progress events, so a splash page can show what the backend is doing.
+const EVENT_STARTING: &str = "backend://starting";
+const EVENT_POLLING: &str = "backend://polling";
+const EVENT_READY: &str = "backend://ready";
+const EVENT_FAILED: &str = "backend://failed";
+// Same payloads as ready/failed under flat names, for frontends that want one
+// definitive "startup finished" signal.
+const EVENT_BACKEND_READY: &str = "backend-ready";
+const EVENT_BACKEND_FAILED: &str = "backend-failed";
+// Carries `{ args, cwd }` of a second launch that handed over to this instance.
+const EVENT_SECOND_INSTANCE: &str = "app://second-instance";
+// Emitted by the watchdog when a running backend dies and is brought back.
+const EVENT_CRASHED: &str = "backend://crashed";
+const EVENT_RESTARTING: &str = "backend://restarting";
+// The backend was stopped on request (stop_backend), not crashed.
+const EVENT_STOPPED: &str = "backend://stopped";
+// Every BackendState change, with the same payload as get_backend_state.
+const EVENT_STATE: &str = "backend://state";
+// `{ theme }` whenever the OS switches between light and dark.
+const EVENT_THEME_CHANGED: &str = "theme-changed";
+// `{ scale_factor, width, height }` to the window that moved to a display with another scale.
+const EVENT_DISPLAY_CHANGED: &str = "display-changed";
+
+fn emit_event(app: &AppHandle, event: &str, payload: serde_json::Value) {
+    splash::on_event(app, event, &payload);
+    let alias = match event {
+        EVENT_READY => Some(EVENT_BACKEND_READY),
+        EVENT_FAILED => Some(EVENT_BACKEND_FAILED),
+        _ => None,
+    };
+    for name in std::iter::once(event).chain(alias) {
+        if let Err(e) = app.emit(name, payload.clone()) {
+            log::warn!("failed to emit {}: {}", name, e);
+        }
+    }
+}
+
+/// `wait_ready` that reports every poll as a `backend://polling` event.
+fn wait_ready_reporting(app: &AppHandle, port: u16, timeout_secs: u64) -> Readiness {
+    let state: tauri::State<AppState> = app.state();
+    let exited = || state.child.lock().unwrap().as_mut().is_some_and(|child| child.has_exited());
+    wait_ready(port, timeout_secs, &state.shutting_down, exited, |attempt| {
+        emit_event(app, EVENT_POLLING, json!({ "port": port, "attempt": attempt }));
+    })
+}
+
+/// Injects the backend's address so the page has it before its own scripts
+/// run; `get_backend_url` stays for pages that miss it.
+fn with_backend_global<'a>(
+    app: &AppHandle,
+    builder: WebviewWindowBuilder<'a, tauri::Wry, AppHandle>,
+) -> WebviewWindowBuilder<'a, tauri::Wry, AppHandle> {
+    match frontend::init_script(app) {
+        Some(script) => builder.initialization_script(&script),
+        None => builder,
+    }
+}
+
+/// Opens the main window on `url`, or points the existing one there (e.g. after
+/// a backend restart or when replacing the error page).
+fn open_main_window(app: &AppHandle, url: &str) -> Result<(), String> {
+    let parsed = window_url(app, url, None)?;
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        return window
+            .navigate(parsed)
+            .map_err(|e| format!("failed to navigate the main window to {}: {}", url, e));
+    }
+    let nav_app = app.clone();
+    let builder = WebviewWindowBuilder::new(app, MAIN_WINDOW, WebviewUrl::External(parsed))
+        .title("Runicorn")
+        .theme(workspace::theme(app, effective_storage_dir(app).as_deref()))
+        .visible(!autostart::hide_first_window(app))
+        .resizable(true)
+        .devtools(devtools_enabled())
+        .on_navigation(move |url| navigation::allow(&nav_app, MAIN_WINDOW, url));
+    let builder = tls::prepare(vibrancy::prepare(app, frontend::prepare(with_backend_global(app, builder))));
+    let window = window_state::apply(app, MAIN_WINDOW, builder)
+        .build()
+        .map_err(|e| format!("failed to create the main window: {}", e))?;
+    vibrancy::apply(app, &window);
+    tls::apply(&window);
+    if devtools_requested() {
+        window.open_devtools();
+    }
+    let zoom = window_state::zoom(app, MAIN_WINDOW);
+    if (zoom - 1.0).abs() > f64::EPSILON {
+        if let Err(e) = window.set_zoom(zoom) {
+            log::warn!("failed to restore zoom {}: {}", zoom, e);
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+enum StartupError {
+    Port(PortInUse),
+    Spawn(SpawnError),
+    NotReady { port: u16, readiness: Readiness },
+    /// The backend is up but its window could not be shown.
+    Window(String),
+    /// The saved remote backend did not answer.
+    Remote { url: String, reason: String },
+    /// Spawning is turned off and no backend URL is configured or saved.
+    NoBackendUrl,
+    /// The app began shutting down mid-startup; the half-started backend was stopped.
+    Cancelled,
+}
+
+impl StartupError {
+    /// Neither the sidecar nor Python can be started, so only the user can fix it.
+    fn no_backend_available(&self) -> bool {
+        matches!(self, StartupError::Spawn(SpawnError::PythonMissing(_) | SpawnError::SidecarMissing(_)))
+    }
+}
+
+impl fmt::Display for StartupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            StartupError::Port(e) => i18n::tf("startup.port", &[e]),
+            StartupError::Spawn(e) => e.to_string(),
+            StartupError::NotReady { port, readiness } => i18n::tf("startup.not_ready", &[port, readiness]),
+            StartupError::Window(e) => i18n::tf("startup.window", &[e]),
+            StartupError::Remote { url, reason } => i18n::tf("startup.remote", &[url, reason]),
+            StartupError::NoBackendUrl => i18n::t("startup.no_backend_url").to_string(),
+            StartupError::Cancelled => i18n::t("startup.cancelled").to_string(),
+        };
+        f.write_str(&text)
+    }
+}
+
+/// Records and announces a lifecycle change as `backend://state`.
+fn set_backend_state(app: &AppHandle, new_state: BackendState) {
+    let state: tauri::State<AppState> = app.state();
+    *state.backend_state.lock().unwrap() = new_state.clone();
+    emit_event(app, EVENT_STATE, json!(new_state));
+}
+
+/// Whether the backend is starting, ready, failed (with the reason) or stopped.
+#[tauri::command]
+fn get_backend_state(state: tauri::State<'_, AppState>) -> BackendState {
+    state.backend_state.lock().unwrap().clone()
+}
+
+/// Attaches to or spawns a backend and waits until it is healthy, emitting
+/// `backend://*` progress events along the way. Returns the backend URL.
+fn boot_backend(app: &AppHandle) -> Result<String, StartupError> {
+    set_backend_state(app, BackendState::Starting);
+    let result = attach_or_spawn(app);
+    match &result {
+        Ok(url) => {
+            // A remote server's pages are untrusted content and get no IPC.
+            if app.state::<AppState>().remote.lock().unwrap().is_none() {
+                capability::allow(app, url);
+            }
+            set_backend_state(app, BackendState::Ready);
+        }
+        Err(StartupError::Cancelled) => set_backend_state(app, BackendState::Stopped),
+        Err(e) => set_backend_state(app, BackendState::Failed(e.to_string())),
+    }
+    result
+}
+
+fn attach_or_spawn(app: &AppHandle) -> Result<String, StartupError> {
+    let state: tauri::State<AppState> = app.state();
+    let pinned = pinned_port(state.requested_port, *state.profile_port.lock().unwrap(), state.config.port);
+    if let Some(path) = &state.pidfile {
+        pidfile::reap_stale(path);
+    }
+
+    // Remote mode never spawns; `child` stays None so kill_child can't reach it.
+    if let Some(url) = state.remote.lock().unwrap().clone() {
+        remote::probe(&url, auth::current().as_deref(), tls::skips_verification(&url)).map_err(|reason| StartupError::Remote { url: url.clone(), reason })?;
+        log::info!("connected to remote backend at {}", url);
+        *state.backend_url.lock().unwrap() = Some(url.clone());
+        record_backend(&state, &url);
+        timings::record(timings::Phase::Healthy);
+        let port = url.parse::<tauri::Url>().ok().and_then(|u| u.port_or_known_default());
+        emit_event(
+            app,
+            EVENT_READY,
+            json!({ "url": url, "port": port, "kind": BackendKind::Remote.label(), "elapsed_ms": 0 }),
+        );
+        return Ok(url);
+    }
+    if no_spawn(&state.config) {
+        return Err(StartupError::NoBackendUrl);
+    }
+
+    // Reuse a backend that is already running (e.g. `runicorn viewer` from a terminal).
+    // `child` stays None so kill_child never touches a process we didn't start.
+    // An external backend has its own storage folder, so skip it once the user picked one.
+    let attach_port = pinned.map(|(p, _)| p).unwrap_or(DEFAULT_PORT);
+    let custom_storage = state.storage_dir.lock().unwrap().is_some();
+    let attach_wanted = state.attach_default.load(Ordering::SeqCst) || (attach_enabled() && !custom_storage);
+    let attach_health = attach_wanted.then(|| check_health(attach_port, PROBE_TIMEOUT));
+    if attach_health == Some(Health::Foreign) {
+        log::warn!("port {} is used by another app, not attaching to it", attach_port);
+    }
+    if attach_health == Some(Health::Healthy) {
+        log::info!("attaching to existing backend on port {}", attach_port);
+        let url = format!("http://{}:{}/", connect_host(), attach_port);
+        *state.backend_url.lock().unwrap() = Some(url.clone());
+        record_backend(&state, &url);
+        timings::record(timings::Phase::Healthy);
+        emit_event(
+            app,
+            EVENT_READY,
+            json!({ "url": url, "port": attach_port, "kind": BackendKind::Attached.label(), "elapsed_ms": 0 }),
+        );
+        return Ok(url);
+    }
+
+    if let Some(dev) = frontend::dev_server() {
+        log::info!("frontend: Vite dev server at {}", dev);
+        capability::allow(app, dev.as_str());
+    } else if frontend::embedded(app) {
+        log::info!("frontend: embedded, served over {}://", frontend::SCHEME);
+    }
+    match effective_storage_dir(app) {
+        Some(dir) => log::info!("backend storage folder: {}", dir.display()),
+        None => log::info!("backend storage folder: backend default (no RUNICORN_DIR set)"),
+    }
+    let timeout_secs = startup_timeout_secs(state.config.startup_timeout);
+    let mut attempt = 1;
+    let (port, readiness) = loop {
+        let reservation = pick_port(pinned).map_err(StartupError::Port)?;
+        let port = reservation.port;
+        timings::record(timings::Phase::PortSelected);
+        if state.shutting_down.load(Ordering::SeqCst) {
+            return Err(StartupError::Cancelled);
+        }
+        log::info!("starting backend on port {} (attempt {}/{})", port, attempt, SPAWN_ATTEMPTS);
+        emit_event(app, EVENT_STARTING, json!({ "port": port }));
+        // Hold the port until the last moment, then hand it to the backend.
+        reservation.release();
+        let readiness = spawn_and_wait(app, port, timeout_secs)?;
+        if attempt < SPAWN_ATTEMPTS && lost_port_race(&readiness, port) {
+            log::warn!("port {} was taken before the backend could bind it, picking another", port);
+            kill_child(&state);
+            thread::sleep(SPAWN_BACKOFF * attempt);
+            attempt += 1;
+            continue;
+        }
+        break (port, readiness);
+    };
+    let url = format!("http://{}:{}/", connect_host(), port);
+    match readiness {
+        Readiness::Ready { elapsed } => {
+            log::info!("backend ready on port {} after {:.1?}", port, elapsed);
+            timings::record(timings::Phase::Healthy);
+            // Set before announcing, so a listener calling get_backend_url sees it.
+            *state.backend_url.lock().unwrap() = Some(url.clone());
+            let kind = state.child.lock().unwrap().as_ref().map(|c| c.kind().label());
+            emit_event(
+                app,
+                EVENT_READY,
+                json!({ "url": url, "port": port, "kind": kind, "elapsed_ms": elapsed.as_millis() as u64 }),
+            );
+        }
+        Readiness::Cancelled => {
+            kill_child(&state);
+            return Err(StartupError::Cancelled);
+        }
+        failed => {
+            emit_event(app, EVENT_FAILED, json!({ "reason": failed.to_string() }));
+            kill_child(&state);
+            return Err(StartupError::NotReady { port, readiness: failed });
+        }
+    }
+    Ok(url)
+}
+
+const SPAWN_ATTEMPTS: u32 = 3;
+// Times the attempt number, between attempts.
+const SPAWN_BACKOFF: Duration = Duration::from_millis(250);
+
+/// The backend died without ever answering and something else now holds its
+/// port: it lost the port to another process between `pick_port` and its bind.
+fn lost_port_race(readiness: &Readiness, port: u16) -> bool {
+    matches!(readiness, Readiness::Exited { .. }) && bind_port(port).is_none()
+}
+
+/// Spawns the backend on `port` and waits for it, falling back from the
+/// sidecar to Python when allowed. The child is left in `state` either way.
+fn spawn_and_wait(app: &AppHandle, port: u16, timeout_secs: u64) -> Result<Readiness, StartupError> {
+    let state: tauri::State<AppState> = app.state();
+    // First attempt: sidecar (preferred for end users)
+    let child = spawn_backend(port, app).map_err(|e| {
+        emit_event(app, EVENT_FAILED, json!({ "reason": e.to_string() }));
+        StartupError::Spawn(e)
+    })?;
+    timings::record(timings::Phase::Spawned);
+    let first_was_sidecar = matches!(child, BackendChild::Sidecar(_));
+
+    set_child(&state, child, port);
+
+    let mut readiness = wait_ready_reporting(app, port, timeout_secs);
+    if let Readiness::Cancelled = readiness {
+        kill_child(&state);
+        return Err(StartupError::Cancelled);
+    }
+    // Only a sidecar that failed is retried with Python; a failed Python would
+    // just fail again. Another app on the port would answer the Python backend
+    // the same way, and one that took the port means a new port, not another backend.
+    let may_fall_back = first_was_sidecar && backend_mode(&state.config) == BackendMode::Auto;
+    if may_fall_back && !readiness.is_ready() && !matches!(readiness, Readiness::Foreign) && !lost_port_race(&readiness, port) {
+        log::warn!("backend on port {} not ready: {}", port, readiness);
+        // Fallback: kill current child and try python-based backend
+        kill_child(&state);
+        match spawn_python_backend(port, app) {
+            Ok(py_child) => {
+                timings::record(timings::Phase::Spawned);
+                set_child(&state, py_child, port);
+                emit_event(app, EVENT_STARTING, json!({ "port": port }));
+                readiness = wait_ready_reporting(app, port, timeout_secs);
+            }
+            Err(e) => log::warn!("python fallback failed: {}", e),
+        }
+    }
+    Ok(readiness)
+}
+
+/// Boots the backend and opens the main window, with the splash up meanwhile.
+fn start(app: &AppHandle) -> Result<(), StartupError> {
+    let state: tauri::State<AppState> = app.state();
+    let _boot = state.boot_lock.lock().unwrap();
+    if app.get_webview_window(MAIN_WINDOW).is_none() && !autostart::start_hidden(app) {
+        splash::open(app);
+    }
+    let url = boot_backend(app)?;
+    open_main_window(app, &url).map_err(StartupError::Window)?;
+    timings::record(timings::Phase::WindowShown);
+    splash::close(app);
+    warn_if_no_frontend(app, &url);
+    explain_moved_port(app, &url);
+    deep_link::flush(app);
+    watchdog::spawn(app);
+    notifications::spawn(app);
+    activity::spawn(app);
+    Ok(())
+}
+
+/// The backend answers `/` with 404 (or nothing) when it got no dist folder
+/// and has no packaged UI, which otherwise shows up as a blank window. Only
+/// probed when we know no frontend was found.
+fn warn_if_no_frontend(app: &AppHandle, url: &str) {
+    if frontend::embedded(app) || frontend::dev_server().is_some() || frontend_dist(app).is_some() {
+        return;
+    }
+    let blank = match auth::get(url).timeout(PROBE_TIMEOUT).call() {
+        Ok(resp) => resp.into_string().map(|body| body.trim().is_empty()).unwrap_or(true),
+        Err(ureq::Error::Status(code, _)) => code == 404,
+        // Unreachable is the watchdog's business, not a packaging problem.
+        Err(_) => false,
+    };
+    if !blank {
+        return;
+    }
+    log::error!("the backend at {} serves no frontend", url);
+    app.dialog()
+        .message(i18n::t("frontend.missing"))
+        .title(i18n::t("dialog.title"))
+        .kind(MessageDialogKind::Error)
+        .show(|_| {});
+}
+
+/// Tells the user, once at startup, why the backend isn't on the usual port
+/// 8000 when we had to move off it: another Runicorn (which they can switch
+/// to) or some other program holds it. Never blocks startup.
+fn explain_moved_port(app: &AppHandle, url: &str) {
+    let state: tauri::State<AppState> = app.state();
+    let pinned = pinned_port(state.requested_port, *state.profile_port.lock().unwrap(), state.config.port).is_some();
+    let moved = url.parse::<tauri::Url>().ok().and_then(|u| u.port()).filter(|p| *p != DEFAULT_PORT);
+    let Some(port) = moved else { return };
+    if pinned || state.child.lock().unwrap().is_none() {
+        return;
+    }
+    let dialog = app.dialog().title(i18n::t("dialog.title")).kind(MessageDialogKind::Info);
+    // A backend another app window started answers only that window's token.
+    let (runicorn, locked) = match auth::get(&health_url(DEFAULT_PORT)).timeout(PROBE_TIMEOUT).call() {
+        Ok(reply) => (is_runicorn_health(reply), false),
+        Err(ureq::Error::Status(401, reply)) => (is_runicorn_auth_challenge(reply), true),
+        Err(_) => (false, false),
+    };
+    if runicorn && locked {
+        log::info!("another Runicorn window's backend holds port {}, backend moved to {}", DEFAULT_PORT, port);
+        dialog
+            .message(i18n::tf("port.other_window", &[&DEFAULT_PORT, &port]))
+            .show(|_| {});
+    } else if runicorn {
+        log::info!("another Runicorn backend holds port {}, offering to use it", DEFAULT_PORT);
+        let app = app.clone();
+        dialog
+            .message(i18n::tf("port.other_runicorn", &[&DEFAULT_PORT, &port, &DEFAULT_PORT]))
+            .buttons(MessageDialogButtons::OkCancelCustom(
+                i18n::t("port.use_it").into(),
+                i18n::t("port.keep_separate").into(),
+            ))
+            .show(move |use_it| {
+                if !use_it {
+                    return;
+                }
+                app.state::<AppState>().attach_default.store(true, Ordering::SeqCst);
+                thread::spawn(move || {
+                    if let Err(e) = restart_backend_blocking(&app) {
+                        log::error!("switching to the backend on port {} failed: {}", DEFAULT_PORT, e);
+                        show_error(&app, &e.to_string());
+                    }
+                });
+            });
+    } else {
+        log::info!("port {} is used by another program, backend moved to {}", DEFAULT_PORT, port);
+        dialog
+            .message(i18n::tf("port.other_program", &[&DEFAULT_PORT, &port]))
+            .show(|_| {});
+    }
+}
+
+/// Runs [`start`] on the async runtime's blocking pool so `setup` returns right away, and turns a
+/// failure into a dialog or error screen rather than a panic.
+fn spawn_start(app: AppHandle) {
+    // A second Retry while a boot is underway would just queue another one.
+    static STARTING: AtomicBool = AtomicBool::new(false);
+    if STARTING.swap(true, Ordering::SeqCst) {
+        log::info!("startup already in progress");
+        return;
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = start(&app);
+        STARTING.store(false, Ordering::SeqCst);
+        match result {
+            Ok(()) => {}
+            Err(StartupError::Cancelled) => log::info!("startup cancelled by shutdown"),
+            Err(StartupError::NoBackendUrl) => {
+                log::info!("no backend URL to connect to; asking for one");
+                splash::close(&app);
+                open_connect_page(&app, "", "");
+            }
+            Err(e) if e.no_backend_available() => {
+                log::error!("no backend can be started: {}", e);
+                splash::close(&app);
+                open_no_backend_page(&app, &e.to_string());
+            }
+            Err(StartupError::Port(e)) => {
+                log::error!("{}", e);
+                show_error(&app, &StartupError::Port(e).to_string());
+                app.exit(1);
+            }
+            Err(e) => {
+                log::error!("backend startup failed: {}", e);
+                report_startup_failure(&app, &e.to_string());
+            }
+        }
+    });
+}
+
+/// Brings the main window to the front, or the splash while still starting.
+fn show_main_window(app: &AppHandle) {
+    let window = app.get_webview_window(MAIN_WINDOW).or_else(|| app.get_webview_window(splash::LABEL));
+    if let Some(window) = window {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Stops the backend, starts a fresh one (possibly on a new port) and points
+/// the main window at it.
+fn restart_backend_blocking(app: &AppHandle) -> Result<String, StartupError> {
+    let state: tauri::State<AppState> = app.state();
+    let _boot = state.boot_lock.lock().unwrap();
+    log::info!("restarting backend");
+    kill_child(&state);
+    let old_url = state.backend_url.lock().unwrap().take();
+    let url = boot_backend(app)?;
+    retarget_windows(app, old_url.as_deref(), &url).map_err(StartupError::Window)?;
+    Ok(url)
+}
+
+/// The page `current` shows, moved to the backend at `new`, with a fresh token.
+fn moved_url(current: &tauri::Url, new: &tauri::Url) -> tauri::Url {
+    let mut moved = new.clone();
+    moved.set_path(current.path());
+    moved.set_fragment(current.fragment());
+    let kept: Vec<(String, String)> =
+        current.query_pairs().filter(|(k, _)| k != auth::QUERY_PARAM).map(|(k, v)| (k.into_owned(), v.into_owned())).collect();
+    moved.query_pairs_mut().clear().extend_pairs(kept);
+    auth::add_to_url(&mut moved);
+    moved
+}
+
+/// Points every window back at the backend after a restart, keeping the page
+/// each one was on: a reload when the backend kept its address (or the window
+/// runs the embedded or dev frontend), the same path on the new port otherwise. The
+/// main window is sent to the start page if it showed something else.
+fn retarget_windows(app: &AppHandle, old: Option<&str>, new: &str) -> Result<(), String> {
+    let old: Option<tauri::Url> = old.and_then(|u| u.parse().ok());
+    let new_parsed: tauri::Url = new.parse().map_err(|e| format!("invalid backend url {}: {}", new, e))?;
+    let mut main_done = false;
+    for (label, window) in app.webview_windows() {
+        let Ok(current) = window.url() else { continue };
+        let on_old = old.as_ref().is_some_and(|o| o.origin() == current.origin());
+        let result = if frontend::is_origin(&current) || (on_old && current.origin() == new_parsed.origin()) {
+            window.reload()
+        } else if on_old {
+            window.navigate(moved_url(&current, &new_parsed))
+        } else {
+            continue;
+        };
+        if let Err(e) = result {
+            log::warn!("failed to reload window {}: {}", label, e);
+        } else if label == MAIN_WINDOW {
+            main_done = true;
+        }
+    }
+    if main_done {
+        return Ok(());
+    }
+    open_main_window(app, new)
+}
+
+/// Points the backend at another storage folder: validates and persists the
+/// path, then restarts the backend so it takes effect. Returns the new URL.
+#[tauri::command]
+async fn set_storage_dir(app: AppHandle, path: String) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || set_storage_dir_blocking(&app, &path)).await.map_err(|e| e.to_string())?
+}
+
+/// Runs `switch` (which returns the new backend URL), saving the current
+/// storage folder's workspace first and restoring the new folder's after, if
+/// the switch changed folders.
+fn switching_workspace(app: &AppHandle, switch: impl FnOnce() -> Result<String, String>) -> Result<String, String> {
+    let before = effective_storage_dir(app);
+    if let Some(dir) = &before {
+        workspace::capture(app, dir);
+    }
+    let url = switch()?;
+    let after = effective_storage_dir(app);
+    if let Some(dir) = after.as_deref().filter(|_| after != before) {
+        workspace::restore(app, dir, &url);
+        tray::refresh(app);
+    }
+    Ok(url)
+}
+
+fn set_storage_dir_blocking(app: &AppHandle, path: &str) -> Result<String, String> {
+    switching_workspace(app, || set_storage_dir_now(app, path))
+}
+
+fn set_storage_dir_now(app: &AppHandle, path: &str) -> Result<String, String> {
+    let dir = storage::validate(path)?;
+    // The attached backend has its own folder.
+    app.state::<AppState>().attach_default.store(false, Ordering::SeqCst);
+    storage::save(app, &dir).map_err(|e| format!("Failed to save the storage folder setting: {}", e))?;
+    log::info!("storage folder set to {}", dir.display());
+    *app.state::<AppState>().storage_dir.lock().unwrap() = Some(dir);
+    tray::refresh(app);
+    restart_backend_blocking(app).map_err(|e| e.to_string())
+}
+
+/// Points the app at the backend at `url`: checks that it answers, saves it
+/// so later launches reconnect, and stops any local backend. `insecure_tls`
+/// accepts a self-signed certificate. Returns the new URL.
+fn switch_to_remote(app: &AppHandle, url: &str, token: Option<String>, insecure_tls: bool) -> Result<String, String> {
+    let url = remote::validate(url)?;
+    remote::probe(&url, token.as_deref(), insecure_tls)
+        .map_err(|reason| format!("No Runicorn backend answered at {}: {}", url, reason))?;
+    remote::save(app, Some((&url, token.as_deref(), insecure_tls)))
+        .map_err(|e| format!("Failed to save the remote backend setting: {}", e))?;
+    log::info!("switching to remote backend {}", url);
+    let state: tauri::State<AppState> = app.state();
+    auth::use_remote(token);
+    tls::set_remote(Some(&url), insecure_tls);
+    *state.remote.lock().unwrap() = Some(url);
+    restart_backend_blocking(app).map_err(|e| e.to_string())
+}
+
+/// Leaves remote mode (if in it) and restarts the local backend, pinned to
+/// `port` if given.
+fn switch_to_local(app: &AppHandle, port: Option<u16>) -> Result<String, String> {
+    remote::save(app, None).map_err(|e| format!("Failed to clear the remote backend setting: {}", e))?;
+    log::info!("switching to a local backend");
+    let state: tauri::State<AppState> = app.state();
+    auth::use_local();
+    tls::set_remote(None, false);
+    *state.remote.lock().unwrap() = None;
+    *state.profile_port.lock().unwrap() = port;
+    restart_backend_blocking(app).map_err(|e| e.to_string())
+}
+
+/// Switches to the backend at `url`, e.g. one on a GPU server, outside any
+/// saved profile; `insecure_tls` accepts a self-signed certificate. Returns
+/// the new URL.
+#[tauri::command]
+async fn connect_remote(app: AppHandle, url: String, insecure_tls: Option<bool>) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let url = switch_to_remote(&app, &url, None, insecure_tls.unwrap_or(false))?;
+        profiles::set_active(&app, None);
+        tray::refresh(&app);
+        Ok(url)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Leaves remote mode and starts a local backend again. Returns the current
+/// URL unchanged when already local.
+fn use_local_backend_blocking(app: &AppHandle) -> Result<String, String> {
+    let state: tauri::State<AppState> = app.state();
+    if state.remote.lock().unwrap().is_none() {
+        return state.backend_url.lock().unwrap().clone().ok_or_else(|| "The backend is not running.".to_string());
+    }
+    let url = switch_to_local(app, None)?;
+    profiles::set_active(app, None);
+    tray::refresh(app);
+    Ok(url)
+}
+
+#[tauri::command]
+async fn use_local_backend(app: AppHandle) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || use_local_backend_blocking(&app))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Saved connection profiles and which one is active.
+#[tauri::command]
+fn list_profiles(app: AppHandle) -> profiles::Profiles {
+    profiles::load(&app)
+}
+
+/// Saves a profile (replacing one with the same name) without switching to it.
+#[tauri::command]
+fn add_profile(app: AppHandle, profile: profiles::Profile) -> Result<(), String> {
+    profiles::add(&app, profile)?;
+    tray::refresh(&app);
+    Ok(())
+}
+
+/// Switches to a saved profile: a local one restarts the local backend with
+/// its port, a remote one connects as `connect_remote` does. Returns the new URL.
+fn activate_profile_blocking(app: &AppHandle, name: &str) -> Result<String, String> {
+    let profile = profiles::find(app, name).ok_or_else(|| format!("There is no profile named {}.", name))?;
+    log::info!("activating profile {}", profile.name);
+    let url = switching_workspace(app, || match profile.target {
+        profiles::Target::Local { port } => switch_to_local(app, port),
+        profiles::Target::Remote { url, token, insecure_tls } => switch_to_remote(app, &url, token, insecure_tls),
+    })?;
+    profiles::set_active(app, Some(&profile.name));
+    tray::refresh(app);
+    Ok(url)
+}
+
+#[tauri::command]
+async fn activate_profile(app: AppHandle, name: String) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || activate_profile_blocking(&app, &name))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Stops the backend without quitting and shows a "start again" page in the
+/// main window. Does nothing when no backend is running.
+fn stop_backend_blocking(app: &AppHandle) {
+    let state: tauri::State<AppState> = app.state();
+    let _boot = state.boot_lock.lock().unwrap();
+    let was_running = state.backend_url.lock().unwrap().take().is_some();
+    kill_child(&state);
+    if !was_running {
+        return;
+    }
+    log::info!("backend stopped on request");
+    emit_event(app, EVENT_STOPPED, json!({}));
+    set_backend_state(app, BackendState::Stopped);
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        let _ = window.navigate(data_url(&i18n::localize(include_str!("stopped.html"))));
+    }
+}
+
+/// Boots the backend after stop_backend and points the main window at it.
+/// Returns the current URL unchanged when a backend is already running.
+fn start_backend_blocking(app: &AppHandle) -> Result<String, StartupError> {
+    let state: tauri::State<AppState> = app.state();
+    let _boot = state.boot_lock.lock().unwrap();
+    let current = state.backend_url.lock().unwrap().clone();
+    if let Some(url) = current {
+        return Ok(url);
+    }
+    let url = boot_backend(app)?;
+    open_main_window(app, &url).map_err(StartupError::Window)?;
+    Ok(url)
+}
+
+/// [`stop_backend_blocking`], first asking for confirmation (unless `force`)
+/// if the backend reports a run in progress. Returns false if the user kept
+/// it running.
+fn stop_backend_confirmed(app: &AppHandle, force: bool) -> bool {
+    let state: tauri::State<AppState> = app.state();
+    let ask = !force && state.config.confirm_stop() && state.child.lock().unwrap().is_some();
+    let active = if ask { running_runs(app) } else { 0 };
+    if active > 0 && !confirm_stop(app, active) {
+        log::info!("stopping the backend cancelled: {} run(s) active", active);
+        return false;
+    }
+    stop_backend_blocking(app);
+    true
+}
+
+/// Frees the backend's port but keeps the app open. Asks first while a run
+/// is active, unless `force` is set for scripted use; returns whether it stopped.
+#[tauri::command]
+async fn stop_backend(app: AppHandle, force: Option<bool>) -> Result<bool, String> {
+    tauri::async_runtime::spawn_blocking(move || stop_backend_confirmed(&app, force.unwrap_or(false)))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Starts the backend again after stop_backend; returns its URL.
+#[tauri::command]
+async fn start_backend(app: AppHandle) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || start_backend_blocking(&app).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Puts the backend URL on the clipboard, with the auth token so it opens
+/// in a browser. Returns the copied URL, or `None` (null) while no backend is up.
+#[tauri::command]
+fn copy_backend_url(app: AppHandle) -> Result<Option<String>, String> {
+    let Some(url) = app.state::<AppState>().backend_url.lock().unwrap().clone() else {
+        log::info!("no backend URL to copy yet");
+        return Ok(None);
+    };
+    let mut url: tauri::Url = url.parse().map_err(|e| format!("invalid backend url {}: {}", url, e))?;
+    auth::add_to_url(&mut url);
+    app.clipboard().write_text(url.to_string()).map_err(|e| format!("Could not copy the backend URL: {}", e))?;
+    Ok(Some(url.into()))
+}
+
+/// Opens the folder holding `backend.log` in the system file manager.
+#[tauri::command]
+fn open_logs_dir(app: AppHandle) -> Result<(), String> {
+    let dir = backend_log::log_dir(&app).map_err(|e| e.to_string())?;
+    if !dir.is_dir() {
+        return Err(format!("The log folder {} does not exist yet.", dir.display()));
+    }
+    app.opener().open_path(dir.to_string_lossy(), None::<&str>).map_err(|e| e.to_string())
+}
+
+/// Recent backend output for the in-app log viewer, at most 5000 lines.
+#[tauri::command]
+fn tail_backend_logs(app: AppHandle, lines: usize) -> Result<String, String> {
+    backend_log::tail(&app, lines).map_err(|e| e.to_string())
+}
+
+/// Turns live `backend://log` line events on or off.
+#[tauri::command]
+fn stream_backend_logs(enabled: bool) {
+    backend_log::set_streaming(enabled);
+}
+
+/// Restarts the backend and returns the new URL. Safe to call while the
+/// current backend is healthy; progress is reported through `backend://*`.
+#[tauri::command]
+async fn restart_backend(app: AppHandle) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || restart_backend_blocking(&app).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Saves the main window's page to `path` (absolute) as `pdf` or `png`.
+/// Fails with a message on platforms whose webview can't render to a file.
+#[tauri::command]
+async fn export_view(app: AppHandle, format: String, path: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let format = export::Format::parse(&format)?;
+        let path = export::validate(&path)?;
+        let window = app.get_webview_window(MAIN_WINDOW).ok_or("the main window is not open")?;
+        export::export(&window, format, &path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Runs the backend reports as running, from the tray's last poll; `null`
+/// while no backend answers.
+#[tauri::command]
+fn get_active_run_count() -> Option<usize> {
+    activity::count()
+}
+
+/// Milliseconds from launch to the end of each phase of the first startup.
+#[tauri::command]
+fn get_startup_timings() -> timings::StartupTimings {
+    timings::get()
+}
+
+/// Whether the app launches at login.
+#[tauri::command]
+fn get_autostart(app: AppHandle) -> bool {
+    autostart::is_enabled(&app)
+}
+
+/// Turns launch at login on or off (it starts in the tray when on).
+#[tauri::command]
+fn set_autostart(app: AppHandle, enabled: bool) -> Result<(), String> {
+    autostart::set(&app, enabled)?;
+    tray::refresh(&app);
+    Ok(())
+}
+
+/// Saves window state (and the storage folder's workspace) and stops the
+/// backend, ahead of exiting or relaunching.
+fn shutdown(app: &AppHandle) {
+    match effective_storage_dir(app) {
+        // Saves the window state along with the workspace.
+        Some(dir) => workspace::capture(app, &dir),
+        None => {
+            if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+                let webview: &tauri::Webview = window.as_ref();
+                window_state::save(&webview.window());
+            }
+        }
+    }
+    let state: tauri::State<AppState> = app.state();
+    cancel_startup(&state);
+    kill_child(&state);
+    // kill_child only clears it for a backend we spawned.
+    if let Some(path) = &state.pidfile {
+        pidfile::remove(path);
+    }
+}
+
+/// A panic on the main thread takes the app down without running `shutdown`,
+/// so stop the backend on the way out. Panics on other threads only end that
+/// thread and leave the backend to the watchdog.
+fn install_panic_hook(app: &AppHandle) {
+    let app = app.clone();
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        previous(info);
+        if thread::current().name() != Some("main") {
+            return;
+        }
+        let state: tauri::State<AppState> = app.state();
+        // The panicking code may be holding the lock; don't deadlock on it.
+        let child = state.child.try_lock().ok().and_then(|mut child| child.take());
+        if let Some(child) = child {
+            log::error!("the app panicked, stopping the backend (pid {})", child.pid());
+            child.stop();
+            if let Some(path) = &state.pidfile {
+                pidfile::remove(path);
+            }
+        }
+    }));
+}
+
+/// Stops the backend on Ctrl+C, SIGTERM/SIGHUP, or the console closing on
+/// Windows, so a terminal-launched app doesn't leave it holding the port. A
+/// second signal exits at once.
+fn install_signal_handler(app: &AppHandle) {
+    static SIGNALLED: AtomicBool = AtomicBool::new(false);
+    let app = app.clone();
+    let installed = ctrlc::set_handler(move || {
+        if SIGNALLED.swap(true, Ordering::SeqCst) {
+            std::process::exit(130);
+        }
+        log::info!("received a termination signal, stopping the backend");
+        quit(&app);
+    });
+    if let Err(e) = installed {
+        log::warn!("failed to install the signal handler: {}", e);
+    }
+}
+
+/// Relaunches the whole app, for settings only read at startup. The backend
+/// is stopped and its port seen free first, so the new instance can't
+/// collide with it. Tauri relaunches with this process's arguments, and the
+/// environment is inherited.
+fn restart_app_blocking(app: &AppHandle) -> ! {
+    log::info!("restarting the app");
+    let state: tauri::State<AppState> = app.state();
+    let spawned = state.child.lock().unwrap().is_some();
+    let url = state.backend_url.lock().unwrap().clone();
+    let port = url.and_then(|u| u.parse::<tauri::Url>().ok()?.port()).filter(|_| spawned);
+    shutdown(app);
+    if let Some(port) = port {
+        if !process::wait_exit(|| bind_port(port).is_some(), process::shutdown_grace()) {
+            log::warn!("port {} is still taken after stopping the backend, restarting anyway", port);
+        }
+    }
+    app.restart()
+}
+
+/// Restarts the app (and with it the backend).
+#[tauri::command]
+async fn restart_app(app: AppHandle) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || restart_app_blocking(&app)).await.map_err(|e| e.to_string())
+}
+
+/// Tells a boot in progress to abort and gives it a moment to stop the
+/// backend it may have just spawned.
+fn cancel_startup(state: &AppState) {
+    state.shutting_down.store(true, Ordering::SeqCst);
+    // A boot notices within one poll (plus a probe timeout) and releases the lock.
+    process::wait_exit(|| state.boot_lock.try_lock().is_ok(), PROBE_TIMEOUT + POLL_INTERVAL * 2);
+}
+
+fn quit(app: &AppHandle) {
+    shutdown(app);
+    app.exit(0);
+}
+
+/// Quits, first asking for confirmation if the backend we are about to stop
+/// reports a run in progress. Runs the check and dialog off the calling thread.
+fn request_quit(app: &AppHandle) {
+    let app = app.clone();
+    thread::spawn(move || {
+        let active = active_runs(&app);
+        if active > 0 && !confirm_quit(&app, active) {
+            return;
+        }
+        quit(&app);
+    });
+}
+
+/// Whether quitting should first check for active runs: only when a backend
+/// we spawned would be stopped, unless `confirm_quit = false`.
+fn wants_quit_check(app: &AppHandle) -> bool {
+    let state: tauri::State<AppState> = app.state();
+    state.config.confirm_quit() && state.child.lock().unwrap().is_some()
+}
+
+/// Active runs when quitting should ask; see [`running_runs`].
+fn active_runs(app: &AppHandle) -> usize {
+    if !wants_quit_check(app) {
+        return 0;
+    }
+    running_runs(app)
+}
+
+/// Whether a run with this status is still going: `running`, or `pending` and
+/// about to. The tray badge, notifications and the quit/stop checks all count
+/// the same runs.
+fn is_active_run(status: &str) -> bool {
+    matches!(status, "running" | "pending")
+}
+
+/// Active runs the backend reports; 0 if it can't tell us quickly, so an
+/// unreachable backend never blocks quitting or stopping.
+fn running_runs(app: &AppHandle) -> usize {
+    let state: tauri::State<AppState> = app.state();
+    let Some(base) = state.backend_url.lock().unwrap().clone() else { return 0 };
+    active_run_count(&base).unwrap_or_else(|e| {
+        log::warn!("could not check for active runs: {}", e);
+        0
+    })
+}
+
+/// How many runs of the backend at `base` are active (see [`is_active_run`]).
+fn active_run_count(base: &str) -> Result<usize, String> {
+    let runs = auth::runs::<serde_json::Value>(base)?;
+    Ok(runs.iter().filter(|run| run["status"].as_str().is_some_and(is_active_run)).count())
+}
+
+fn confirm_stop(app: &AppHandle, active: usize) -> bool {
+    let message = if active == 1 { i18n::t("stop.confirm_one").to_string() } else { i18n::tf("stop.confirm_many", &[&active]) };
+    app.dialog()
+        .message(message)
+        .title(i18n::t("dialog.title"))
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(i18n::t("stop.anyway").into(), i18n::t("dialog.cancel").into()))
+        .blocking_show()
+}
+
+fn confirm_quit(app: &AppHandle, active: usize) -> bool {
+    let message = if active == 1 { i18n::t("quit.confirm_one").to_string() } else { i18n::tf("quit.confirm_many", &[&active]) };
+    app.dialog()
+        .message(message)
+        .title(i18n::t("dialog.title"))
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(i18n::t("quit.anyway").into(), i18n::t("dialog.cancel").into()))
+        .blocking_show()
+}
+
+/// Whether closing the main window hides it to the tray (the default) rather
+/// than quitting. `RUNICORN_CLOSE_TO_TRAY=0` restores close-means-quit.
+fn close_to_tray(app: &AppHandle) -> bool {
+    let enabled = !matches!(std::env::var("RUNICORN_CLOSE_TO_TRAY").as_deref(), Ok("0") | Ok("false"));
+    // never hide the only way back in
+    enabled && app.tray_by_id(tray::TRAY_ID).is_some()
+}
+
+fn main() {
+    timings::mark_launch();
+    tauri::Builder::default()
+        // Must come first: a second launch exits inside this plugin, before setup
+        // runs, so it never spawns a backend or reaches kill_child. With the
+        // `deep-link` feature it also hands any runicorn:// URL to the deep-link plugin.
+        .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+            log::info!("second launch handed over: {:?}", args);
+            show_main_window(app);
+            emit_event(app, EVENT_SECOND_INSTANCE, json!({ "args": args, "cwd": cwd }));
+        }))
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_log::Builder::new().build())
+        .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(tauri_plugin_autostart::init(
+            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+            Some(vec![autostart::HIDDEN_ARG]),
+        ))
+        .register_asynchronous_uri_scheme_protocol(frontend::SCHEME, |ctx, request, responder| {
+            let app = ctx.app_handle().clone();
+            tauri::async_runtime::spawn_blocking(move || responder.respond(frontend::handle(&app, request)));
+        })
+        .menu(menu::build)
+        .on_menu_event(menu::handle)
+        .setup(move |app| {
+            // Managed here rather than on the builder: config paths need the app handle.
+            let config = config::load(app.handle());
+            init_health_path(&config);
+            proxy::init(&config);
+            // An explicit env var wins; otherwise the folder picked in the UI, then runicorn.toml.
+            let storage_dir = if storage::ENV_KEYS.iter().any(|k| std::env::var_os(k).is_some()) {
+                None
+            } else {
+                storage::load(app.handle()).or_else(|| config.storage_dir.clone())
+            };
+            // In thin-client mode a configured URL wins over one saved by connect_remote.
+            let remote = match configured_backend_url(&config) {
+                Some(url) => Some((url, None, false)),
+                None => remote::load(app.handle()),
+            };
+            if let Some((_, token, _)) = &remote {
+                auth::use_remote(token.clone());
+            }
+            tls::set_remote(remote.as_ref().map(|(url, _, _)| url.as_str()), remote.as_ref().is_some_and(|r| r.2));
+            let profile_port = match profiles::active(app.handle()).map(|p| p.target) {
+                Some(profiles::Target::Local { port }) => port,
+                _ => None,
+            };
+            app.manage(AppState {
+                child: Mutex::new(None),
+                backend_url: Mutex::new(None),
+                backend_state: Mutex::new(BackendState::Starting),
+                // Read here rather than first thing in main, so a bad value gets logged.
+                requested_port: cli_port(),
+                config,
+                storage_dir: Mutex::new(storage_dir),
+                remote: Mutex::new(remote.map(|(url, _, _)| url)),
+                profile_port: Mutex::new(profile_port),
+                pidfile: pidfile::path(app.handle()),
+                boot_lock: Mutex::new(()),
+                shutting_down: AtomicBool::new(false),
+                log_level: Mutex::new(None),
+                attach_default: AtomicBool::new(false),
+            });
+            install_signal_handler(app.handle());
+            install_panic_hook(app.handle());
+            deep_link::init(app.handle());
+            if let Err(e) = tray::build(app.handle()) {
+                log::warn!("failed to create tray icon: {}", e);
+            }
+            hotkey::register(app.handle());
+            if app.state::<AppState>().config.check_for_updates() {
+                updater::check_in_background(app.handle(), false);
+            }
+            // spawn backend in a background thread to avoid blocking
+            spawn_start(app.handle().clone());
+            Ok(())
+        })
+        .on_window_event(|window, event| {
+            // Every window sees the change; report it once.
+            if let WindowEvent::ThemeChanged(theme) = event {
+                if window.label() == MAIN_WINDOW {
+                    log::info!("OS theme changed to {}", theme_label(*theme));
+                    emit_event(window.app_handle(), EVENT_THEME_CHANGED, json!({ "theme": theme_label(*theme) }));
+                }
+            }
+            if let WindowEvent::ScaleFactorChanged { scale_factor, new_inner_size, .. } = event {
+                log::info!("window {} now at scale factor {}", window.label(), scale_factor);
+                // Re-applying the size makes the webview lay out again at the new scale
+                // instead of keeping the old one until the next resize.
+                #[cfg(not(target_os = "macos"))]
+                if let Err(e) = window.set_size(*new_inner_size) {
+                    log::warn!("failed to relayout window {}: {}", window.label(), e);
+                }
+                let payload =
+                    json!({ "scale_factor": scale_factor, "width": new_inner_size.width, "height": new_inner_size.height });
+                if let Err(e) = window.app_handle().emit_to(window.label(), EVENT_DISPLAY_CHANGED, payload) {
+                    log::warn!("failed to emit {}: {}", EVENT_DISPLAY_CHANGED, e);
+                }
+            }
+            if let WindowEvent::CloseRequested { api, .. } = event {
                 let app = window.app_handle();
-                let state: tauri::State<AppState> = app.state();
-                kill_child(&state);
+                let is_main = window.label() == MAIN_WINDOW;
+                if is_main {
+                    match effective_storage_dir(app) {
+                        // Saves the window state along with the workspace.
+                        Some(dir) => workspace::capture(app, &dir),
+                        None => window_state::save(window),
+                    }
+                    if close_to_tray(app) {
+                        api.prevent_close();
+                        let _ = window.hide();
+                        return;
+                    }
+                }
+                // Comparison windows share the backend: closing one only closes it,
+                // unless it is the last window left (the splash doesn't count).
+                let others = app.webview_windows().into_keys().any(|l| l != window.label() && l != splash::LABEL);
+                if is_main || !others {
+                    api.prevent_close();
+                    if wants_quit_check(app) {
+                        request_quit(app);
+                    } else {
+                        quit(app);
+                    }
+                }
             }
         })
-        .invoke_handler(tauri::generate_handler![get_backend_url])
+        .invoke_handler(tauri::generate_handler![
+            get_backend_url,
+            get_backend_info,
+            open_compare_window,
+            set_window_title,
+            set_always_on_top,
+            get_theme,
+            set_theme,
+            get_autostart,
+            set_autostart,
+            export_view,
+            get_startup_timings,
+            get_active_run_count,
+            set_zoom,
+            reset_zoom,
+            restart_backend,
+            get_backend_status,
+            get_backend_state,
+            open_logs_dir,
+            tail_backend_logs,
+            stream_backend_logs,
+            set_storage_dir,
+            get_app_version,
+            get_platform_info,
+            get_backend_resources,
+            copy_backend_url,
+            reload_app,
+            restart_app,
+            collect_diagnostics,
+            stop_backend,
+            start_backend,
+            connect_remote,
+            use_local_backend,
+            list_profiles,
+            add_profile,
+            activate_profile,
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -0,0 +1,220 @@
+//! The menu bar: File, Edit, View, Backend, Window and Help, plus the app
+//! menu on macOS, where it sits in the system menu bar (elsewhere each window
+//! gets it). Items run the same code as the tray and the matching commands;
+//! View's accelerators give the browser-style reload and zoom shortcuts.
+
+use std::thread;
+
+use tauri::{
+    menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu, HELP_SUBMENU_ID, WINDOW_SUBMENU_ID},
+    AppHandle, Manager, WebviewWindow, Wry,
+};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::i18n::t;
+
+const ZOOM_STEP: f64 = 0.1;
+
+// Ids differ from the tray's, whose events reach this handler too.
+const HANDLED: [&str; 12] = [
+    "open-storage-dir",
+    "reload",
+    "devtools",
+    "zoom-in",
+    "zoom-out",
+    "zoom-reset",
+    "menu-restart-backend",
+    "menu-copy-url",
+    "menu-open-logs",
+    "menu-about",
+    "help-check-updates",
+    "menu-quit",
+];
+
+pub fn build(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let menu = Menu::new(app)?;
+    let about = MenuItem::with_id(app, "menu-about", t("tray.about"), true, None::<&str>)?;
+    // Not the predefined Quit, which exits natively and skips the active-run
+    // check and the backend shutdown.
+    let quit = MenuItem::with_id(app, "menu-quit", t("tray.quit"), true, Some("CmdOrCtrl+Q"))?;
+
+    #[cfg(target_os = "macos")]
+    menu.append(&Submenu::with_items(
+        app,
+        &app.package_info().name,
+        true,
+        &[
+            &about,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::services(app, None)?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::hide(app, None)?,
+            &PredefinedMenuItem::hide_others(app, None)?,
+            &PredefinedMenuItem::show_all(app, None)?,
+            &PredefinedMenuItem::separator(app)?,
+            &quit,
+        ],
+    )?)?;
+
+    let file = Submenu::with_items(
+        app,
+        t("menu.file"),
+        true,
+        &[
+            &MenuItem::with_id(app, "open-storage-dir", t("menu.open_storage_dir"), true, Some("CmdOrCtrl+O"))?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::close_window(app, None)?,
+        ],
+    )?;
+    #[cfg(not(target_os = "macos"))]
+    file.append(&quit)?;
+    menu.append(&file)?;
+
+    // Without it, copy and paste shortcuts do nothing in the webview on macOS.
+    menu.append(&Submenu::with_items(
+        app,
+        t("menu.edit"),
+        true,
+        &[
+            &PredefinedMenuItem::undo(app, None)?,
+            &PredefinedMenuItem::redo(app, None)?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::cut(app, None)?,
+            &PredefinedMenuItem::copy(app, None)?,
+            &PredefinedMenuItem::paste(app, None)?,
+            &PredefinedMenuItem::select_all(app, None)?,
+        ],
+    )?)?;
+
+    let view = Submenu::with_items(
+        app,
+        t("menu.view"),
+        true,
+        &[
+            &MenuItem::with_id(app, "reload", t("menu.reload"), true, Some("CmdOrCtrl+R"))?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, "zoom-in", t("menu.zoom_in"), true, Some("CmdOrCtrl+="))?,
+            &MenuItem::with_id(app, "zoom-out", t("menu.zoom_out"), true, Some("CmdOrCtrl+-"))?,
+            &MenuItem::with_id(app, "zoom-reset", t("menu.actual_size"), true, Some("CmdOrCtrl+0"))?,
+        ],
+    )?;
+    #[cfg(target_os = "macos")]
+    view.append(&PredefinedMenuItem::fullscreen(app, None)?)?;
+    if crate::devtools_enabled() {
+        view.append(&PredefinedMenuItem::separator(app)?)?;
+        view.append(&MenuItem::with_id(app, "devtools", t("menu.devtools"), true, Some("CmdOrCtrl+Shift+I"))?)?;
+    }
+    menu.append(&view)?;
+
+    menu.append(&Submenu::with_items(
+        app,
+        t("menu.backend"),
+        true,
+        &[
+            &MenuItem::with_id(app, "menu-restart-backend", t("tray.restart_backend"), true, None::<&str>)?,
+            &MenuItem::with_id(app, "menu-copy-url", t("tray.copy_url"), true, None::<&str>)?,
+            &MenuItem::with_id(app, "menu-open-logs", t("tray.open_logs"), true, None::<&str>)?,
+        ],
+    )?)?;
+
+    menu.append(&Submenu::with_id_and_items(
+        app,
+        WINDOW_SUBMENU_ID,
+        t("menu.window"),
+        true,
+        &[&PredefinedMenuItem::minimize(app, None)?, &PredefinedMenuItem::maximize(app, None)?],
+    )?)?;
+
+    let help = Submenu::with_id_and_items(
+        app,
+        HELP_SUBMENU_ID,
+        t("menu.help"),
+        true,
+        &[&MenuItem::with_id(app, "help-check-updates", t("menu.check_updates"), true, None::<&str>)?],
+    )?;
+    #[cfg(not(target_os = "macos"))]
+    help.append(&about)?;
+    menu.append(&help)?;
+    Ok(menu)
+}
+
+/// The window the user is looking at, else the main one.
+fn focused_window(app: &AppHandle) -> Option<WebviewWindow> {
+    let windows = app.webview_windows();
+    windows
+        .values()
+        .find(|w| w.is_focused().unwrap_or(false))
+        .cloned()
+        .or_else(|| windows.get(crate::MAIN_WINDOW).cloned())
+}
+
+/// Runs `action` off the main thread, since it may block, and shows its error.
+fn in_background(app: &AppHandle, what: &'static str, action: impl FnOnce(&AppHandle) -> Result<(), String> + Send + 'static) {
+    let app = app.clone();
+    thread::spawn(move || {
+        if let Err(e) = action(&app) {
+            log::error!("{} failed: {}", what, e);
+            crate::show_error(&app, &e);
+        }
+    });
+}
+
+/// Lets the user pick a storage folder and switches the backend to it.
+fn open_storage_dir(app: &AppHandle) -> Result<(), String> {
+    let Some(picked) = app.dialog().file().set_title(t("menu.open_storage_dir")).blocking_pick_folder() else {
+        return Ok(());
+    };
+    let path = picked.into_path().map_err(|e| e.to_string())?;
+    crate::set_storage_dir_blocking(app, &path.to_string_lossy()).map(|_| ())
+}
+
+pub fn handle(app: &AppHandle, event: MenuEvent) {
+    let id = event.id.as_ref();
+    if !HANDLED.contains(&id) {
+        return;
+    }
+    match id {
+        "open-storage-dir" => return in_background(app, "opening a storage folder", open_storage_dir),
+        "menu-restart-backend" => {
+            return in_background(app, "backend restart", |app| {
+                crate::restart_backend_blocking(app).map(|_| ()).map_err(|e| e.to_string())
+            });
+        }
+        "menu-copy-url" => {
+            return in_background(app, "copying the backend URL", |app| crate::copy_backend_url(app.clone()).map(|_| ()));
+        }
+        "menu-open-logs" => return in_background(app, "opening the logs folder", |app| crate::open_logs_dir(app.clone())),
+        "menu-about" => {
+            let app = app.clone();
+            thread::spawn(move || crate::about::show(&app));
+            return;
+        }
+        "help-check-updates" => return crate::updater::check_in_background(app, true),
+        "menu-quit" => return crate::request_quit(app),
+        _ => {}
+    }
+    let Some(window) = focused_window(app) else { return };
+    if id == "devtools" {
+        if window.is_devtools_open() {
+            window.close_devtools();
+        } else {
+            window.open_devtools();
+        }
+        return;
+    }
+    if id == "reload" {
+        if let Err(e) = crate::reload_window(&window) {
+            log::warn!("failed to reload window {}: {}", window.label(), e);
+        }
+        return;
+    }
+    let current = crate::window_state::zoom(app, window.label());
+    let factor = match id {
+        "zoom-in" => current + ZOOM_STEP,
+        "zoom-out" => current - ZOOM_STEP,
+        _ => 1.0,
+    };
+    if let Err(e) = crate::apply_zoom(&window, factor) {
+        log::warn!("failed to zoom window {}: {}", window.label(), e);
+    }
+}
@@ -0,0 +1,123 @@
+//! Keeps the webview pinned to Runicorn: backend pages navigate in place,
+//! anything off-site opens in the default browser instead.
+
+use std::{collections::HashSet, sync::Mutex, thread};
+
+use tauri::{AppHandle, Manager, Url};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::AppState;
+
+// Enough for the bundled frontend; antd injects inline styles at runtime.
+const DEFAULT_CSP: &str = "default-src 'self'; script-src 'self'; style-src 'self' 'unsafe-inline'; \
+img-src 'self' data: blob:; font-src 'self' data:; worker-src 'self' blob:; \
+connect-src 'self' ws://127.0.0.1:* ws://localhost:* ws://[::1]:*; frame-ancestors 'none'";
+
+/// Policy the backend sends with every page: `RUNICORN_CSP`, else `csp` from
+/// runicorn.toml, else [`DEFAULT_CSP`]. An empty value turns it off.
+pub fn content_security_policy(app: &AppHandle) -> Option<String> {
+    let policy = match std::env::var("RUNICORN_CSP") {
+        Ok(v) => v,
+        Err(_) => {
+            let state: tauri::State<AppState> = app.state();
+            state.config.csp.clone().unwrap_or_else(|| {
+                // The log WebSocket goes straight to the backend, which RUNICORN_HOST may move off loopback.
+                let host = crate::connect_host();
+                match host.as_str() {
+                    "127.0.0.1" | "[::1]" => DEFAULT_CSP.to_string(),
+                    _ => DEFAULT_CSP.replace("connect-src 'self'", &format!("connect-src 'self' ws://{}:*", host)),
+                }
+            })
+        }
+    };
+    let policy = policy.trim().to_string();
+    (!policy.is_empty()).then_some(policy)
+}
+
+// The app's own data: pages, the only ones whose action links are honored.
+static OWN_PAGES: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// Remembers `url` as one of the app's own pages.
+pub fn register_own_page(url: &Url) {
+    OWN_PAGES.lock().unwrap().get_or_insert_with(HashSet::new).insert(url.to_string());
+}
+
+/// Whether window `label` currently shows one of the app's own pages. Any
+/// page can navigate to a `data:` URL, so the scheme alone proves nothing.
+fn on_own_page(app: &AppHandle, label: &str) -> bool {
+    let Some(current) = app.get_webview_window(label).and_then(|w| w.url().ok()) else { return false };
+    OWN_PAGES.lock().unwrap().as_ref().is_some_and(|pages| pages.contains(current.as_str()))
+}
+
+fn is_internal(app: &AppHandle, url: &Url) -> bool {
+    if crate::frontend::is_origin(url) {
+        return true;
+    }
+    match url.scheme() {
+        // error/splash pages and in-page blobs
+        "data" | "about" | "blob" | "tauri" => return true,
+        "http" | "https" => {}
+        _ => return false,
+    }
+    let state: tauri::State<AppState> = app.state();
+    let backend = state.backend_url.lock().unwrap().clone();
+    match backend.as_deref().and_then(|b| b.parse::<Url>().ok()) {
+        // Only the backend itself; other local servers (e.g. TensorBoard) open in the browser.
+        Some(backend) if backend.origin() == url.origin() => return true,
+        Some(_) => {}
+        // Mid-restart there is no URL yet, and the backend may come back on any port.
+        None if matches!(url.host_str(), Some("127.0.0.1" | "localhost" | "[::1]")) => return true,
+        None => {}
+    }
+    if crate::remote::is_remote_page(app, url) {
+        return true;
+    }
+    state.config.allowed_urls.iter().any(|prefix| url.as_str().starts_with(prefix.as_str()))
+}
+
+/// `on_navigation` handler for window `label`: true lets the webview navigate,
+/// false means the URL was handed to the system browser (or was an action).
+pub fn allow(app: &AppHandle, label: &str, url: &Url) -> bool {
+    // Buttons on the "backend stopped", connect and no-backend pages.
+    if url.host_str() == Some(crate::splash::ACTION_HOST) {
+        if !on_own_page(app, label) {
+            log::warn!("ignoring {} from a page that isn't the app's own", url.path());
+            return false;
+        }
+        match url.path() {
+            "/start-backend" => {
+                let app = app.clone();
+                // Only reachable from the "backend stopped" page; with spawning turned off
+                // this connects to the configured URL or asks for one, never spawns.
+                thread::spawn(move || match crate::start_backend_blocking(&app) {
+                    Ok(_) => {}
+                    Err(crate::StartupError::NoBackendUrl) => crate::open_connect_page(&app, "", ""),
+                    Err(e) => {
+                        log::error!("backend start failed: {}", e);
+                        crate::show_error(&app, &e.to_string());
+                    }
+                });
+            }
+            "/retry" => crate::spawn_start(app.clone()),
+            "/copy-diagnostics" => {
+                let app = app.clone();
+                thread::spawn(move || crate::copy_diagnostics_from_page(&app));
+            }
+            "/connect" => {
+                let entered = url.query_pairs().find(|(k, _)| k == "url").map(|(_, v)| v.into_owned()).unwrap_or_default();
+                let app = app.clone();
+                thread::spawn(move || crate::connect_entered_url(&app, &entered));
+            }
+            _ => {}
+        }
+        return false;
+    }
+    if is_internal(app, url) {
+        return true;
+    }
+    log::info!("opening {} in the default browser", url);
+    if let Err(e) = app.opener().open_url(url.as_str(), None::<&str>) {
+        log::warn!("failed to open {}: {}", url, e);
+    }
+    false
+}
@@ -0,0 +1,134 @@
+//! Desktop notifications when a run finishes, so the app can sit in the tray
+//! during a long training job. Polls the backend's run list on its own thread,
+//! separate from the health watchdog.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::Deserialize;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::{
+    i18n::{t, tf},
+    AppState,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+// More finishes than this in one poll become a single summary notification.
+const MAX_INDIVIDUAL: usize = 3;
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Deserialize)]
+struct Run {
+    id: String,
+    status: String,
+    #[serde(default)]
+    created_time: Option<f64>,
+    #[serde(default)]
+    alias: Option<String>,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    best_metric_name: Option<String>,
+    #[serde(default)]
+    best_metric_value: Option<f64>,
+}
+
+impl Run {
+    fn is_active(&self) -> bool {
+        crate::is_active_run(&self.status)
+    }
+
+    fn name(&self) -> String {
+        let label = self.alias.as_deref().or(self.path.as_deref()).filter(|s| !s.is_empty());
+        match label {
+            Some(label) => format!("{} ({})", label, self.id),
+            None => self.id.clone(),
+        }
+    }
+
+    fn summary(&self) -> String {
+        match (&self.best_metric_name, self.best_metric_value) {
+            (Some(metric), Some(value)) => format!("{} {}: {} = {}", self.name(), self.status, metric, value),
+            _ => format!("{} {}", self.name(), self.status),
+        }
+    }
+}
+
+/// Starts the poller unless `notify_runs = false`; later calls are no-ops.
+pub fn spawn(app: &AppHandle) {
+    if !app.state::<AppState>().config.notify_runs() || STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    thread::spawn(move || run(app));
+}
+
+fn fetch(app: &AppHandle) -> Option<(String, Vec<Run>)> {
+    let state: tauri::State<AppState> = app.state();
+    if state.shutting_down.load(Ordering::SeqCst) {
+        return None;
+    }
+    let base = state.backend_url.lock().unwrap().clone()?;
+    match crate::auth::runs::<Run>(&base) {
+        Ok(runs) => Some((base, runs)),
+        Err(e) => {
+            log::debug!("{}", e);
+            None
+        }
+    }
+}
+
+fn run(app: AppHandle) {
+    let all_runs = app.state::<AppState>().config.notify_all_runs();
+    let session_start = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or_default();
+    let mut backend: Option<String> = None;
+    // Runs seen active on the last poll, and runs already notified about.
+    let mut active: HashSet<String> = HashSet::new();
+    let mut notified: HashSet<String> = HashSet::new();
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let Some((base, runs)) = fetch(&app) else { continue };
+        // Another backend (restart on a new port, remote, profile) has other runs.
+        if backend.as_deref() != Some(base.as_str()) {
+            backend = Some(base);
+            active = runs.iter().filter(|r| r.is_active()).map(|r| r.id.clone()).collect();
+            continue;
+        }
+        let by_id: HashMap<&str, &Run> = runs.iter().map(|r| (r.id.as_str(), r)).collect();
+        let finished: Vec<&Run> = active
+            .iter()
+            .filter_map(|id| by_id.get(id.as_str()).copied())
+            .filter(|r| !r.is_active() && !notified.contains(&r.id))
+            .filter(|r| all_runs || r.created_time.is_some_and(|t| t >= session_start))
+            .collect();
+        notify(&app, &finished);
+        notified.extend(finished.iter().map(|r| r.id.clone()));
+        active = runs.iter().filter(|r| r.is_active()).map(|r| r.id.clone()).collect();
+    }
+}
+
+fn notify(app: &AppHandle, finished: &[&Run]) {
+    if finished.len() > MAX_INDIVIDUAL {
+        let names: Vec<String> = finished.iter().map(|r| r.name()).collect();
+        show(app, &tf("notify.finished_many", &[&finished.len()]), &names.join("\n"));
+        return;
+    }
+    for run in finished {
+        let title = if run.status == "failed" { t("notify.failed") } else { t("notify.finished") };
+        show(app, title, &run.summary());
+    }
+}
+
+fn show(app: &AppHandle, title: &str, body: &str) {
+    log::info!("{}: {}", title, body);
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        log::warn!("failed to show a notification: {}", e);
+    }
+}
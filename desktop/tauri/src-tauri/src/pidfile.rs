@@ -0,0 +1,101 @@
+//! Records the app and its backend in `<app config dir>/runicorn-desktop.pid`:
+//! scripts read it to find the backend and tell whether Runicorn is up, and
+//! the next launch after a crash or force-quit uses it to stop a backend the
+//! previous run left holding its port.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::process;
+
+const PID_FILE_NAME: &str = "runicorn-desktop.pid";
+// Guards against acting on some other program's file of the same name.
+const MARKER: &str = "runicorn-desktop";
+
+#[derive(Serialize, Deserialize)]
+struct Record {
+    marker: String,
+    desktop_pid: u32,
+    /// `None` for a backend we attached or connected to rather than spawned.
+    backend_pid: Option<u32>,
+    port: u16,
+    kind: String,
+    url: String,
+}
+
+pub fn path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(PID_FILE_NAME))
+}
+
+pub fn write(path: &Path, backend_pid: Option<u32>, port: u16, kind: &str, url: &str) {
+    let record = Record {
+        marker: MARKER.to_string(),
+        desktop_pid: std::process::id(),
+        backend_pid,
+        port,
+        kind: kind.to_string(),
+        url: url.to_string(),
+    };
+    let result = path
+        .parent()
+        .map(fs::create_dir_all)
+        .unwrap_or(Ok(()))
+        .and_then(|_| fs::write(path, serde_json::to_string(&record).unwrap_or_default()));
+    if let Err(e) = result {
+        log::warn!("failed to write {}: {}", path.display(), e);
+    }
+}
+
+pub fn remove(path: &Path) {
+    let _ = fs::remove_file(path);
+}
+
+fn is_runicorn_backend(command_line: &str) -> bool {
+    let lower = command_line.to_ascii_lowercase();
+    // Windows only reports the image name, so a python.exe is the best we can check there.
+    lower.contains("runicorn-viewer") || (lower.contains("python") && (cfg!(windows) || lower.contains("runicorn")))
+}
+
+/// Stops a backend left over from a run that never shut down cleanly, then
+/// drops the stale pidfile. The app that wrote it must be gone, and the
+/// process must still look like one of our backends with its port still
+/// taken, so a live instance's backend and a reused pid are left alone.
+pub fn reap_stale(path: &Path) {
+    let Ok(text) = fs::read_to_string(path) else { return };
+    let record = match serde_json::from_str::<Record>(&text) {
+        Ok(record) if record.marker == MARKER => record,
+        _ => {
+            log::warn!("ignoring unrecognized pidfile {}", path.display());
+            remove(path);
+            return;
+        }
+    };
+    // Another instance still running owns the file and its backend.
+    if record.desktop_pid != std::process::id() && process::command_line(record.desktop_pid).is_some() {
+        log::info!("pidfile {} belongs to a running instance (pid {}), leaving it alone", path.display(), record.desktop_pid);
+        return;
+    }
+    // Nothing of ours to stop behind an attached or remote backend.
+    let Some(pid) = record.backend_pid else {
+        remove(path);
+        return;
+    };
+    match process::command_line(pid) {
+        Some(cmd) if is_runicorn_backend(&cmd) && crate::bind_port(record.port).is_none() => {
+            log::warn!("stopping {} backend (pid {}, port {}) left over from a previous run", record.kind, pid, record.port);
+            let grace = process::shutdown_grace();
+            let asked = process::request_terminate(pid);
+            if !asked || !process::wait_exit(|| process::command_line(pid).is_none(), grace) {
+                process::force_kill(pid);
+            }
+        }
+        Some(_) => log::info!("pid {} from a stale pidfile is no longer a Runicorn backend, leaving it alone", pid),
+        None => {}
+    }
+    remove(path);
+}
@@ -0,0 +1,65 @@
+//! OS, architecture and OS version, for platform-aware UI and bug reports.
+
+use std::{
+    process::{Command, Stdio},
+    sync::OnceLock,
+};
+
+use serde::Serialize;
+
+use crate::process::hide_console;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlatformInfo {
+    /// `windows`, `macos`, `linux`, … as in `std::env::consts::OS`.
+    pub os: &'static str,
+    /// `x86_64`, `aarch64`, … as in `std::env::consts::ARCH`.
+    pub arch: &'static str,
+    /// e.g. `14.5` on macOS or `Ubuntu 24.04 LTS`; `None` if it couldn't be read.
+    pub version: Option<String>,
+    /// `sidecar`, `python`, `attached` or `remote`; `None` while no backend is up.
+    pub backend: Option<&'static str>,
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let mut cmd = Command::new(program);
+    cmd.args(args).stdin(Stdio::null());
+    hide_console(&mut cmd);
+    let out = cmd.output().ok()?;
+    let text = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    (out.status.success() && !text.is_empty()).then_some(text)
+}
+
+#[cfg(target_os = "macos")]
+fn read_os_version() -> Option<String> {
+    command_output("sw_vers", &["-productVersion"])
+}
+
+#[cfg(target_os = "windows")]
+fn read_os_version() -> Option<String> {
+    // "Microsoft Windows [Version 10.0.22631.3880]"
+    let text = command_output("cmd", &["/C", "ver"])?;
+    let start = text.find("Version ")? + "Version ".len();
+    Some(text[start..].trim_end_matches(']').to_string())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn read_os_version() -> Option<String> {
+    let release = std::fs::read_to_string("/etc/os-release").ok();
+    let pretty = release.as_deref().and_then(|text| {
+        text.lines()
+            .find_map(|line| line.strip_prefix("PRETTY_NAME="))
+            .map(|v| v.trim_matches('"').to_string())
+    });
+    pretty.or_else(|| command_output("uname", &["-r"]))
+}
+
+/// The OS version, looked up once per launch.
+pub fn os_version() -> Option<String> {
+    static VERSION: OnceLock<Option<String>> = OnceLock::new();
+    VERSION.get_or_init(read_os_version).clone()
+}
+
+pub fn info(backend: Option<&'static str>) -> PlatformInfo {
+    PlatformInfo { os: std::env::consts::OS, arch: std::env::consts::ARCH, version: os_version(), backend }
+}
@@ -0,0 +1,149 @@
+//! Platform helpers for managing backend processes.
+
+use std::{
+    process::Command,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Keep helper commands from flashing a console window in the GUI build on Windows.
+pub fn hide_console(cmd: &mut Command) {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    #[cfg(not(target_os = "windows"))]
+    let _ = cmd;
+}
+
+/// Asks a process to exit on its own: SIGTERM on Unix, Ctrl+Break on Windows.
+/// Returns whether the request was delivered.
+pub fn request_terminate(pid: u32) -> bool {
+    #[cfg(unix)]
+    {
+        // SAFETY: kill(2) has no memory-safety preconditions.
+        unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) == 0 }
+    }
+    #[cfg(windows)]
+    {
+        send_ctrl_break(pid)
+    }
+}
+
+// Our own copy of the Ctrl+Break we broadcast; without a handler it would end this process.
+#[cfg(windows)]
+unsafe extern "system" fn ignore_ctrl_break(_ctrl_type: u32) -> windows::core::BOOL {
+    true.into()
+}
+
+/// Sends Ctrl+Break to every process on `pid`'s console, i.e. the backend and
+/// its children. The backend runs without a window, so WM_CLOSE (`taskkill`
+/// without `/F`) never reaches uvicorn; Ctrl+Break raises SIGBREAK, which the
+/// viewer turns into the same graceful shutdown as Ctrl+C (see
+/// `runicorn.viewer.install_sigbreak_handler`; tests/unit/test_sigbreak_shutdown.py
+/// covers that side). Attaching to the backend's console instead of spawning it
+/// in a new process group works for the sidecar too, which the shell plugin
+/// starts without a way to pass creation flags.
+#[cfg(windows)]
+fn send_ctrl_break(pid: u32) -> bool {
+    use windows::Win32::System::Console::{
+        AttachConsole, FreeConsole, GenerateConsoleCtrlEvent, SetConsoleCtrlHandler, ATTACH_PARENT_PROCESS,
+        CTRL_BREAK_EVENT,
+    };
+    // SAFETY: plain Win32 calls; the handler is a static function that stays valid.
+    unsafe {
+        // A GUI build has no console of its own; a debug build leaves its terminal and rejoins it below.
+        let _ = FreeConsole();
+        if AttachConsole(pid).is_err() {
+            if cfg!(debug_assertions) {
+                let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+            }
+            return false;
+        }
+        let _ = SetConsoleCtrlHandler(Some(ignore_ctrl_break), true);
+        // Group 0: everything attached to this console, including uvicorn's workers.
+        let sent = GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, 0).is_ok();
+        let _ = FreeConsole();
+        if cfg!(debug_assertions) {
+            let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+        }
+        sent
+    }
+}
+
+/// Force-kills `pid` together with every process it spawned. `Child::kill`
+/// only reaches the immediate child, which leaves uvicorn's own children
+/// holding the port on Windows.
+#[cfg(target_os = "windows")]
+pub fn kill_tree(pid: u32) -> bool {
+    use std::process::Stdio;
+    let mut cmd = Command::new("taskkill");
+    cmd.args(["/PID", &pid.to_string(), "/T", "/F"]).stdout(Stdio::null()).stderr(Stdio::null());
+    hide_console(&mut cmd);
+    cmd.status().map(|s| s.success()).unwrap_or(false)
+}
+
+/// Force-kills `pid` (and on Windows its children) without a grace period.
+pub fn force_kill(pid: u32) -> bool {
+    #[cfg(unix)]
+    {
+        // SAFETY: kill(2) has no memory-safety preconditions.
+        unsafe { libc::kill(pid as libc::pid_t, libc::SIGKILL) == 0 }
+    }
+    #[cfg(windows)]
+    {
+        kill_tree(pid)
+    }
+}
+
+/// What `pid` is running: the full command line on Unix, the image name on
+/// Windows. `None` when no such process exists.
+pub fn command_line(pid: u32) -> Option<String> {
+    #[cfg(unix)]
+    let mut cmd = {
+        let mut cmd = Command::new("ps");
+        cmd.args(["-p", &pid.to_string(), "-o", "command="]);
+        cmd
+    };
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut cmd = Command::new("tasklist");
+        cmd.args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"]);
+        cmd
+    };
+    hide_console(&mut cmd);
+    let output = cmd.output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    // tasklist prints an "INFO: No tasks..." line instead of failing
+    if !output.status.success() || text.is_empty() || text.starts_with("INFO:") {
+        return None;
+    }
+    Some(text)
+}
+
+/// Polls `exited` until it reports true or `grace` elapses.
+pub fn wait_exit(mut exited: impl FnMut() -> bool, grace: Duration) -> bool {
+    let deadline = Instant::now() + grace;
+    loop {
+        if exited() {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// How long a backend gets to shut down after the terminate request,
+/// overridable via `RUNICORN_SHUTDOWN_GRACE` (seconds).
+pub fn shutdown_grace() -> Duration {
+    std::env::var("RUNICORN_SHUTDOWN_GRACE")
+        .ok()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+        .map(Duration::from_secs_f64)
+        .unwrap_or(Duration::from_secs(3))
+}
@@ -0,0 +1,113 @@
+//! Named connections (a local backend or a remote URL) saved to
+//! `<app config dir>/profiles.json`, for switching between environments from
+//! the UI or the tray.
+
+use std::{fs, io, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+const PROFILES_FILE_NAME: &str = "profiles.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Target {
+    /// Spawn a backend on this machine, optionally pinned to `port`.
+    Local {
+        #[serde(default)]
+        port: Option<u16>,
+    },
+    /// Connect to a backend elsewhere, sending `token` if it requires one;
+    /// `insecure_tls` accepts a self-signed HTTPS certificate.
+    Remote {
+        url: String,
+        #[serde(default)]
+        token: Option<String>,
+        #[serde(default)]
+        insecure_tls: bool,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    #[serde(flatten)]
+    pub target: Target,
+}
+
+/// What `list_profiles` returns.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Profiles {
+    #[serde(default)]
+    pub profiles: Vec<Profile>,
+    /// Name of the profile in use; `None` after connecting by hand.
+    #[serde(default)]
+    pub active: Option<String>,
+}
+
+fn file_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(PROFILES_FILE_NAME))
+}
+
+pub fn load(app: &AppHandle) -> Profiles {
+    let Some(path) = file_path(app) else { return Profiles::default() };
+    let Ok(text) = fs::read_to_string(&path) else { return Profiles::default() };
+    match serde_json::from_str::<Profiles>(&text) {
+        Ok(profiles) => profiles,
+        Err(e) => {
+            log::warn!("ignoring malformed {}: {}", path.display(), e);
+            Profiles::default()
+        }
+    }
+}
+
+fn save(app: &AppHandle, profiles: &Profiles) -> io::Result<()> {
+    let path = file_path(app).ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no app config directory"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(profiles).unwrap_or_default())
+}
+
+pub fn find(app: &AppHandle, name: &str) -> Option<Profile> {
+    load(app).profiles.into_iter().find(|p| p.name == name)
+}
+
+/// The active profile, if it still exists.
+pub fn active(app: &AppHandle) -> Option<Profile> {
+    let profiles = load(app);
+    let name = profiles.active?;
+    profiles.profiles.into_iter().find(|p| p.name == name)
+}
+
+/// Adds `profile`, replacing one with the same name. Remote URLs are checked
+/// and normalized; port 0 means "no preference".
+pub fn add(app: &AppHandle, mut profile: Profile) -> Result<(), String> {
+    profile.name = profile.name.trim().to_string();
+    if profile.name.is_empty() {
+        return Err("The profile name is empty.".to_string());
+    }
+    match &mut profile.target {
+        Target::Local { port } => *port = port.filter(|p| *p != 0),
+        Target::Remote { url, token, .. } => {
+            *url = crate::remote::validate(url)?;
+            *token = token.take().filter(|t| !t.trim().is_empty());
+        }
+    }
+    let mut profiles = load(app);
+    profiles.profiles.retain(|p| p.name != profile.name);
+    profiles.profiles.push(profile);
+    save(app, &profiles).map_err(|e| format!("Failed to save the profiles: {}", e))
+}
+
+/// Records which profile is in use.
+pub fn set_active(app: &AppHandle, name: Option<&str>) {
+    let mut profiles = load(app);
+    if profiles.active.as_deref() == name {
+        return;
+    }
+    profiles.active = name.map(str::to_string);
+    if let Err(e) = save(app, &profiles) {
+        log::warn!("failed to save the active profile: {}", e);
+    }
+}
@@ -0,0 +1,119 @@
+//! HTTP proxy for calls that leave the machine: the updater and remote
+//! backends. `proxy` in runicorn.toml, else `HTTPS_PROXY`, `HTTP_PROXY` or
+//! `ALL_PROXY`; hosts in `NO_PROXY` (plus `no_proxy` from the file) and
+//! loopback always go direct, so local health checks never touch it.
+
+use std::{net::IpAddr, sync::OnceLock};
+
+use tauri::Url;
+
+use crate::config::Config;
+
+const ENV_KEYS: [&str; 6] = ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"];
+
+struct Settings {
+    proxy: Option<Url>,
+    no_proxy: Vec<String>,
+}
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+static DIRECT: OnceLock<ureq::Agent> = OnceLock::new();
+static PROXIED: OnceLock<Option<ureq::Agent>> = OnceLock::new();
+
+fn env(keys: &[&str]) -> Option<(String, String)> {
+    keys.iter().find_map(|k| std::env::var(k).ok().filter(|v| !v.trim().is_empty()).map(|v| (k.to_string(), v)))
+}
+
+/// Reads the proxy settings once at startup. An empty `proxy` in the file
+/// turns the proxy off even when the environment sets one.
+pub fn init(config: &Config) {
+    let chosen = match config.proxy.as_deref().map(str::trim) {
+        Some("") => None,
+        Some(raw) => Some((crate::config::CONFIG_FILE_NAME.to_string(), raw.to_string())),
+        None => env(&ENV_KEYS),
+    };
+    let proxy = chosen.and_then(|(source, raw)| {
+        // `host:port` without a scheme is common in these variables.
+        let with_scheme = if raw.contains("://") { raw.clone() } else { format!("http://{}", raw) };
+        match with_scheme.parse::<Url>() {
+            Ok(url) if url.host_str().is_some() => {
+                log::info!("outbound requests use the proxy {} (from {})", redacted(&url), source);
+                Some(url)
+            }
+            _ => {
+                log::warn!("ignoring proxy {:?} from {}: not a valid URL", raw, source);
+                None
+            }
+        }
+    });
+    let mut no_proxy: Vec<String> = env(&["NO_PROXY", "no_proxy"])
+        .map(|(_, v)| v.split(',').map(|s| s.trim().to_ascii_lowercase()).filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    no_proxy.extend(config.no_proxy.iter().map(|s| s.trim().to_ascii_lowercase()));
+    let _ = SETTINGS.set(Settings { proxy, no_proxy });
+}
+
+/// The proxy URL without its password, for logs.
+fn redacted(url: &Url) -> String {
+    let mut url = url.clone();
+    if url.password().is_some() {
+        let _ = url.set_password(Some("***"));
+    }
+    url.to_string()
+}
+
+fn is_loopback(host: &str) -> bool {
+    let bare = host.trim_start_matches('[').trim_end_matches(']');
+    bare.eq_ignore_ascii_case("localhost") || bare.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Whether `host` skips the proxy: loopback, or a `NO_PROXY` entry (`*`, the
+/// host itself, or a parent domain like `.example.com`).
+fn bypassed(host: &str, no_proxy: &[String]) -> bool {
+    if is_loopback(host) {
+        return true;
+    }
+    let host = host.to_ascii_lowercase();
+    no_proxy.iter().any(|entry| {
+        // Entries may carry a port; only the host matters here.
+        let entry = entry.split_once(':').filter(|_| !entry.starts_with('[')).map_or(entry.as_str(), |(name, _)| name);
+        let domain = entry.trim_start_matches('.');
+        entry == "*" || host == domain || host.ends_with(&format!(".{}", domain))
+    })
+}
+
+/// The proxy for requests to `url`, `None` when they go direct.
+pub fn for_url(url: &str) -> Option<&'static Url> {
+    let settings = SETTINGS.get()?;
+    let proxy = settings.proxy.as_ref()?;
+    let host = url.parse::<Url>().ok()?.host_str()?.to_string();
+    (!bypassed(&host, &settings.no_proxy)).then_some(proxy)
+}
+
+/// The proxy for the update feed (GitHub releases), if any.
+pub fn for_updates() -> Option<&'static Url> {
+    for_url("https://github.com/")
+}
+
+/// The HTTP client for `url`: through the proxy when [`for_url`] says so, and
+/// without certificate checks for a remote backend that allows that.
+pub fn agent(url: &str) -> ureq::Agent {
+    if crate::tls::skips_verification(url) {
+        return crate::tls::insecure_agent(for_url(url));
+    }
+    let direct = || DIRECT.get_or_init(ureq::agent).clone();
+    if for_url(url).is_none() {
+        return direct();
+    }
+    let proxied = PROXIED.get_or_init(|| {
+        let proxy = SETTINGS.get()?.proxy.as_ref()?;
+        match ureq::Proxy::new(proxy.as_str()) {
+            Ok(proxy) => Some(ureq::AgentBuilder::new().proxy(proxy).build()),
+            Err(e) => {
+                log::warn!("ignoring proxy {}: {}", redacted(proxy), e);
+                None
+            }
+        }
+    });
+    proxied.clone().unwrap_or_else(direct)
+}
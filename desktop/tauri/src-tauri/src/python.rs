@@ -0,0 +1,106 @@
+//! Locates a usable Python interpreter for the fallback backend.
+
+use std::{
+    fmt,
+    process::{Command, Stdio},
+};
+
+use crate::process::hide_console;
+
+/// Oldest Python the viewer supports.
+pub const MIN_VERSION: (u32, u32) = (3, 8);
+
+/// An interpreter invocation such as `python3` or `py -3`.
+#[derive(Debug, Clone)]
+pub struct PythonCmd {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl PythonCmd {
+    fn new(program: &str, args: &[&str]) -> Self {
+        PythonCmd { program: program.to_string(), args: args.iter().map(|a| a.to_string()).collect() }
+    }
+
+    /// A `Command` ready for further arguments.
+    pub fn command(&self) -> Command {
+        let mut cmd = Command::new(&self.program);
+        cmd.args(&self.args);
+        cmd
+    }
+}
+
+impl fmt::Display for PythonCmd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.program)?;
+        for arg in &self.args {
+            write!(f, " {}", arg)?;
+        }
+        Ok(())
+    }
+}
+
+/// Candidates in priority order: the `RUNICORN_DESKTOP_PY` override, then the usual names.
+fn candidates() -> Vec<PythonCmd> {
+    let mut list = Vec::new();
+    if let Ok(custom) = std::env::var("RUNICORN_DESKTOP_PY") {
+        if !custom.trim().is_empty() {
+            list.push(PythonCmd::new(custom.trim(), &[]));
+        }
+    }
+    list.push(PythonCmd::new("python3", &[]));
+    list.push(PythonCmd::new("python", &[]));
+    if cfg!(target_os = "windows") {
+        list.push(PythonCmd::new("py", &["-3"]));
+    }
+    list
+}
+
+// Runs actual code rather than `--version`, which store stubs and broken
+// installs can answer without being able to import anything.
+const VERSION_SCRIPT: &str = "import sys; print(sys.version_info[:2])";
+
+/// Parses the script's `(3, 11)` into `(3, 11)`.
+fn parse_version(text: &str) -> Option<(u32, u32)> {
+    let inner = text.trim().strip_prefix('(')?.strip_suffix(')')?;
+    let (major, minor) = inner.split_once(',')?;
+    Some((major.trim().parse().ok()?, minor.trim().parse().ok()?))
+}
+
+fn probe_version(py: &PythonCmd) -> Option<(u32, u32)> {
+    let mut cmd = py.command();
+    cmd.args(["-c", VERSION_SCRIPT]).stdin(Stdio::null());
+    hide_console(&mut cmd);
+    let out = cmd.output().ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    parse_version(&String::from_utf8_lossy(&out.stdout))
+}
+
+/// Why no interpreter could be used.
+#[derive(Debug)]
+pub struct NotFound {
+    /// Every candidate with the reason it was skipped.
+    pub tried: Vec<String>,
+    /// The first interpreter that ran but is older than [`MIN_VERSION`].
+    pub too_old: Option<(PythonCmd, (u32, u32))>,
+}
+
+/// Returns the first interpreter reporting at least [`MIN_VERSION`], or a
+/// description of every candidate that was tried.
+pub fn find() -> Result<(PythonCmd, (u32, u32)), NotFound> {
+    let mut missing = NotFound { tried: Vec::new(), too_old: None };
+    for py in candidates() {
+        match probe_version(&py) {
+            Some(version) if version >= MIN_VERSION => return Ok((py, version)),
+            Some((major, minor)) => {
+                log::warn!("`{}` is Python {}.{}, older than {}.{}", py, major, minor, MIN_VERSION.0, MIN_VERSION.1);
+                missing.tried.push(format!("{} ({}.{}, too old)", py, major, minor));
+                missing.too_old.get_or_insert((py, (major, minor)));
+            }
+            None => missing.tried.push(format!("{} (not found)", py)),
+        }
+    }
+    Err(missing)
+}
@@ -0,0 +1,101 @@
+//! A backend running elsewhere (e.g. on a GPU server) that the app connects to
+//! instead of spawning one. Chosen with `connect_remote` and persisted to
+//! `<app config dir>/remote.json` so the next launch reconnects.
+
+use std::{fs, io, path::PathBuf, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Url};
+
+const REMOTE_FILE_NAME: &str = "remote.json";
+// Remote links are slower than loopback.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Default, Serialize, Deserialize)]
+struct Stored {
+    url: Option<String>,
+    #[serde(default)]
+    token: Option<String>,
+    #[serde(default)]
+    insecure_tls: bool,
+}
+
+fn file_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(REMOTE_FILE_NAME))
+}
+
+/// The saved remote URL, its token and whether it may use a self-signed
+/// certificate, if the app is in remote mode.
+pub fn load(app: &AppHandle) -> Option<(String, Option<String>, bool)> {
+    let path = file_path(app)?;
+    let text = fs::read_to_string(&path).ok()?;
+    match serde_json::from_str::<Stored>(&text) {
+        Ok(stored) => stored.url.map(|url| (url, stored.token, stored.insecure_tls)),
+        Err(e) => {
+            log::warn!("ignoring malformed {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Saves the remote URL, token and certificate flag, or forgets the remote
+/// (back to local mode) for `None`.
+pub fn save(app: &AppHandle, remote: Option<(&str, Option<&str>, bool)>) -> io::Result<()> {
+    let path = file_path(app).ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no app config directory"))?;
+    let Some((url, token, insecure_tls)) = remote else {
+        return match fs::remove_file(&path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let stored = Stored { url: Some(url.to_string()), token: token.map(str::to_string), insecure_tls };
+    fs::write(&path, serde_json::to_string_pretty(&stored).unwrap_or_default())
+}
+
+/// Checks that `raw` is an http(s) URL and returns it with a trailing slash,
+/// the form `backend_url` uses.
+pub fn validate(raw: &str) -> Result<String, String> {
+    let raw = raw.trim();
+    let url: Url = raw.parse().map_err(|e| format!("{} is not a valid URL: {}", raw, e))?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err(format!("{} is not an http:// or https:// address.", raw));
+    }
+    let mut url = url.to_string();
+    if !url.ends_with('/') {
+        url.push('/');
+    }
+    Ok(url)
+}
+
+/// Whether a Runicorn backend answers at `url` (sending `token` only if given,
+/// never our own, and accepting a self-signed certificate if `insecure_tls`);
+/// the error says why not.
+pub fn probe(url: &str, token: Option<&str>, insecure_tls: bool) -> Result<(), String> {
+    let health = crate::health_url_for(url);
+    let agent = if crate::tls::allowed(url, insecure_tls) {
+        crate::tls::insecure_agent(crate::proxy::for_url(&health))
+    } else {
+        crate::proxy::agent(&health)
+    };
+    let mut request = agent.get(&health);
+    if let Some(token) = token {
+        request = request.set(crate::auth::HEADER, token);
+    }
+    match request.timeout(PROBE_TIMEOUT).call() {
+        Ok(reply) if crate::is_runicorn_health(reply) => Ok(()),
+        Ok(_) => Err("it answered, but not as a Runicorn backend".to_string()),
+        Err(ureq::Error::Status(code, _)) => Err(format!("its health check answered with HTTP {}", code)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Whether `url` is on the same origin as the remote backend.
+pub fn is_remote_page(app: &AppHandle, url: &Url) -> bool {
+    let state: tauri::State<crate::AppState> = app.state();
+    let remote = state.remote.lock().unwrap();
+    let Some(remote) = remote.as_deref().and_then(|r| r.parse::<Url>().ok()) else { return false };
+    remote.origin() == url.origin()
+}
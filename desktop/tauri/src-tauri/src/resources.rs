@@ -0,0 +1,65 @@
+//! CPU and memory use of the backend we spawned, for a gauge in the UI.
+//!
+//! Sums over the backend's whole process tree: the PyInstaller sidecar runs
+//! the server in a child of its bootloader, and uvicorn may fork workers.
+
+use std::{
+    sync::Mutex,
+    thread,
+    time::Instant,
+};
+
+use serde::Serialize;
+use sysinfo::{Pid, ProcessRefreshKind, RefreshKind, System, MINIMUM_CPU_UPDATE_INTERVAL};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendResources {
+    pub pid: u32,
+    /// Share of one core, as `top` reports it (can exceed 100 on several cores).
+    pub cpu_percent: f32,
+    /// Resident memory in bytes.
+    pub memory_bytes: u64,
+    /// Processes counted: the backend and its descendants.
+    pub processes: usize,
+}
+
+// Kept between calls: CPU usage is measured from one refresh to the next.
+static SYSTEM: Mutex<Option<(System, Instant)>> = Mutex::new(None);
+
+fn refresh(system: &mut System) {
+    system.refresh_processes_specifics(ProcessRefreshKind::new().with_cpu().with_memory());
+}
+
+/// Usage of `pid` and its descendants; `None` once the process is gone.
+pub fn sample(pid: u32) -> Option<BackendResources> {
+    let mut guard = SYSTEM.lock().unwrap();
+    let (system, last) = guard.get_or_insert_with(|| {
+        let mut system = System::new_with_specifics(RefreshKind::new());
+        refresh(&mut system);
+        (system, Instant::now())
+    });
+    // Too soon after the previous refresh and CPU usage reads as 0.
+    let since = last.elapsed();
+    if since < MINIMUM_CPU_UPDATE_INTERVAL {
+        thread::sleep(MINIMUM_CPU_UPDATE_INTERVAL - since);
+    }
+    refresh(system);
+    *last = Instant::now();
+
+    let root = Pid::from_u32(pid);
+    system.process(root)?;
+    let mut tree = vec![root];
+    let mut i = 0;
+    while i < tree.len() {
+        let parent = tree[i];
+        tree.extend(system.processes().iter().filter(|(_, p)| p.parent() == Some(parent)).map(|(pid, _)| *pid));
+        i += 1;
+    }
+    let processes: Vec<_> = tree.iter().filter_map(|pid| system.process(*pid)).collect();
+    Some(BackendResources {
+        pid,
+        cpu_percent: processes.iter().map(|p| p.cpu_usage()).sum(),
+        memory_bytes: processes.iter().map(|p| p.memory()).sum(),
+        processes: processes.len(),
+    })
+}
@@ -0,0 +1,88 @@
+//! Small frameless window shown while the backend starts.
+//!
+//! The page is a static `data:` URL driven from Rust with `eval`, so it works
+//! before any backend exists. Its Retry/Quit buttons navigate to
+//! `https://runicorn.invalid/...`, which the navigation handler intercepts.
+
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::i18n::{localize, t, tf};
+
+pub const LABEL: &str = "splash";
+/// Host of the fake URLs our local pages use as buttons; never resolves.
+pub const ACTION_HOST: &str = "runicorn.invalid";
+
+fn window(app: &AppHandle) -> Option<tauri::WebviewWindow> {
+    app.get_webview_window(LABEL)
+}
+
+fn call(app: &AppHandle, function: &str, text: &str) {
+    if let Some(w) = window(app) {
+        let arg = serde_json::to_string(text).unwrap_or_default();
+        let _ = w.eval(&format!("{}({})", function, arg));
+    }
+}
+
+/// Shows the splash (or resets it to the progress view if already open).
+pub fn open(app: &AppHandle) {
+    if window(app).is_some() {
+        set_status(app, t("splash.starting"));
+        return;
+    }
+    let handle = app.clone();
+    let builder = WebviewWindowBuilder::new(app, LABEL, WebviewUrl::External(crate::data_url(&localize(include_str!("splash.html")))))
+        .title("Runicorn")
+        .inner_size(420.0, 260.0)
+        .resizable(false)
+        .decorations(false)
+        .center()
+        .on_navigation(move |url| {
+            if url.host_str() != Some(ACTION_HOST) {
+                return true;
+            }
+            match url.path() {
+                "/retry" => {
+                    crate::spawn_start(handle.clone());
+                }
+                // Same path as the menu Quit, so a half-started backend and its pidfile go too.
+                "/quit" => crate::request_quit(&handle),
+                _ => {}
+            }
+            false
+        });
+    // Same browser arguments as the other windows, or WebView2 refuses them.
+    if let Err(e) = crate::tls::prepare(builder).build() {
+        log::warn!("failed to create splash window: {}", e);
+    }
+}
+
+pub fn set_status(app: &AppHandle, text: &str) {
+    call(app, "setStatus", text);
+}
+
+/// Switches the splash to its error view. Returns false when there is no
+/// splash to show it in.
+pub fn show_error(app: &AppHandle, message: &str) -> bool {
+    if window(app).is_none() {
+        return false;
+    }
+    call(app, "showError", message);
+    true
+}
+
+/// Maps backend progress events to a status line.
+pub fn on_event(app: &AppHandle, event: &str, payload: &serde_json::Value) {
+    let text = match event {
+        crate::EVENT_STARTING => tf("splash.starting_port", &[&payload["port"]]),
+        crate::EVENT_POLLING => tf("splash.polling", &[&payload["attempt"]]),
+        crate::EVENT_READY => t("splash.ready").to_string(),
+        _ => return,
+    };
+    set_status(app, &text);
+}
+
+pub fn close(app: &AppHandle) {
+    if let Some(w) = window(app) {
+        let _ = w.destroy();
+    }
+}
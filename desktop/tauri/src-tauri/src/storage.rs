@@ -0,0 +1,92 @@
+//! The Runicorn storage root the backend reads runs from. Chosen from the UI
+//! via `set_storage_dir` and persisted to `<app config dir>/storage.json`,
+//! along with the last few folders used for the tray's Recent Folders menu.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+const STORAGE_FILE_NAME: &str = "storage.json";
+const MAX_RECENT: usize = 8;
+// The backend reads RUNICORN_DIR; RUNICORN_STORAGE_DIR is set too for launchers that use it.
+pub const ENV_KEYS: [&str; 2] = ["RUNICORN_DIR", "RUNICORN_STORAGE_DIR"];
+
+#[derive(Default, Serialize, Deserialize)]
+struct Stored {
+    storage_dir: Option<PathBuf>,
+    /// Most recent first, including `storage_dir`.
+    #[serde(default)]
+    recent: Vec<PathBuf>,
+}
+
+fn file_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(STORAGE_FILE_NAME))
+}
+
+fn read(app: &AppHandle) -> Stored {
+    let Some(path) = file_path(app) else { return Stored::default() };
+    let Ok(text) = fs::read_to_string(&path) else { return Stored::default() };
+    serde_json::from_str(&text).unwrap_or_else(|e| {
+        log::warn!("ignoring malformed {}: {}", path.display(), e);
+        Stored::default()
+    })
+}
+
+fn write(app: &AppHandle, stored: &Stored) -> io::Result<()> {
+    let path = file_path(app).ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no app config directory"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(stored).unwrap_or_default())
+}
+
+/// The saved storage directory, if the user picked one.
+pub fn load(app: &AppHandle) -> Option<PathBuf> {
+    read(app).storage_dir
+}
+
+/// Saves `dir` as the storage directory and moves it to the top of the recent list.
+pub fn save(app: &AppHandle, dir: &Path) -> io::Result<()> {
+    let mut stored = read(app);
+    stored.storage_dir = Some(dir.to_path_buf());
+    stored.recent.retain(|d| d != dir);
+    stored.recent.insert(0, dir.to_path_buf());
+    stored.recent.truncate(MAX_RECENT);
+    write(app, &stored)
+}
+
+/// Recently used storage directories, most recent first.
+pub fn recent(app: &AppHandle) -> Vec<PathBuf> {
+    read(app).recent
+}
+
+/// Drops `dir` from the recent list, e.g. once it has been deleted.
+pub fn forget(app: &AppHandle, dir: &Path) {
+    let mut stored = read(app);
+    stored.recent.retain(|d| d != dir);
+    if let Err(e) = write(app, &stored) {
+        log::warn!("failed to update the recent storage folders: {}", e);
+    }
+}
+
+/// Checks that `raw` names an existing, writable directory and returns it
+/// canonicalized.
+pub fn validate(raw: &str) -> Result<PathBuf, String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Err("The storage folder path is empty.".to_string());
+    }
+    let dir = fs::canonicalize(raw).map_err(|e| format!("The storage folder {} does not exist: {}", raw, e))?;
+    if !dir.is_dir() {
+        return Err(format!("{} is not a folder.", dir.display()));
+    }
+    // Metadata permissions are unreliable across platforms; try an actual write.
+    let probe = dir.join(".runicorn-write-test");
+    fs::write(&probe, b"").map_err(|e| format!("The storage folder {} is not writable: {}", dir.display(), e))?;
+    let _ = fs::remove_file(&probe);
+    Ok(dir)
+}
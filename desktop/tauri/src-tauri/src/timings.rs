@@ -0,0 +1,82 @@
+//! How long each phase of the first startup took, measured from launch, for
+//! `get_startup_timings`, the diagnostics report and one log line. Backend
+//! restarts later on don't touch it.
+
+use std::{
+    sync::{Mutex, OnceLock},
+    time::Instant,
+};
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Phase {
+    PortSelected,
+    Spawned,
+    Healthy,
+    WindowShown,
+}
+
+/// Milliseconds since launch at the end of each phase; `None` for phases not
+/// reached yet or skipped (an attached or remote backend has no port or spawn).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StartupTimings {
+    pub port_selected_ms: Option<u64>,
+    pub spawned_ms: Option<u64>,
+    pub healthy_ms: Option<u64>,
+    pub window_shown_ms: Option<u64>,
+}
+
+static LAUNCHED: OnceLock<Instant> = OnceLock::new();
+static TIMINGS: Mutex<StartupTimings> = Mutex::new(StartupTimings {
+    port_selected_ms: None,
+    spawned_ms: None,
+    healthy_ms: None,
+    window_shown_ms: None,
+});
+
+/// Starts the clock; call first thing in `main`.
+pub fn mark_launch() {
+    LAUNCHED.get_or_init(Instant::now);
+}
+
+/// Records that `phase` just ended. A retried phase keeps its latest time;
+/// nothing changes once the window has been shown.
+pub fn record(phase: Phase) {
+    let Some(launched) = LAUNCHED.get() else { return };
+    let ms = launched.elapsed().as_millis() as u64;
+    let mut timings = TIMINGS.lock().unwrap();
+    if timings.window_shown_ms.is_some() {
+        return;
+    }
+    let slot = match phase {
+        Phase::PortSelected => &mut timings.port_selected_ms,
+        Phase::Spawned => &mut timings.spawned_ms,
+        Phase::Healthy => &mut timings.healthy_ms,
+        Phase::WindowShown => &mut timings.window_shown_ms,
+    };
+    *slot = Some(ms);
+    if let Phase::WindowShown = phase {
+        log::info!("startup timings: {}", summary_of(&timings));
+    }
+}
+
+pub fn get() -> StartupTimings {
+    TIMINGS.lock().unwrap().clone()
+}
+
+fn summary_of(t: &StartupTimings) -> String {
+    let ms = |v: Option<u64>| v.map(|v| format!("{} ms", v)).unwrap_or_else(|| "-".to_string());
+    format!(
+        "port selected {}, backend spawned {}, first healthy reply {}, window shown {}",
+        ms(t.port_selected_ms),
+        ms(t.spawned_ms),
+        ms(t.healthy_ms),
+        ms(t.window_shown_ms)
+    )
+}
+
+/// One line for the diagnostics report.
+pub fn summary() -> String {
+    summary_of(&get())
+}
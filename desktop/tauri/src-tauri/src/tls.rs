@@ -0,0 +1,174 @@
+//! Accepting a self-signed certificate from a remote backend served over
+//! HTTPS, for teams who run both ends. Off unless `RUNICORN_INSECURE_TLS=1`
+//! (any remote backend) or the remote's `insecure_tls` flag (that one) is set.
+//! Only requests to the remote's origin skip verification; plain HTTP, the
+//! local backend and the updater are unaffected.
+
+use std::sync::{Arc, Mutex, OnceLock};
+
+use rustls::{
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::{self, CryptoProvider},
+    pki_types::{CertificateDer, ServerName, UnixTime},
+    DigitallySignedStruct, SignatureScheme,
+};
+use tauri::{Url, WebviewWindow, WebviewWindowBuilder};
+
+pub const ENV_KEY: &str = "RUNICORN_INSECURE_TLS";
+
+// The remote origin that skips verification, if any.
+static INSECURE_ORIGIN: Mutex<Option<String>> = Mutex::new(None);
+// Whether the webviews were set up to accept bad certificates at launch.
+static WEBVIEW_INSECURE: OnceLock<bool> = OnceLock::new();
+
+fn env_enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| {
+        std::env::var(ENV_KEY).is_ok_and(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+    })
+}
+
+/// Sets the remote backend the app talks to (`None` for a local one) and
+/// whether its certificate may be self-signed. Call it whenever the remote
+/// changes; the first call also decides how the webviews are set up.
+pub fn set_remote(url: Option<&str>, insecure: bool) {
+    let origin = url.and_then(|u| insecure_origin(u, insecure));
+    let insecure = origin.is_some();
+    if let Some(origin) = &origin {
+        log::warn!("TLS certificate verification is DISABLED for {}; anyone on the network path can impersonate it", origin);
+    }
+    if WEBVIEW_INSECURE.set(insecure).is_err() && insecure && !webview_insecure() {
+        log::warn!("the window only accepts the self-signed certificate after Runicorn restarts");
+    }
+    *INSECURE_ORIGIN.lock().unwrap() = origin;
+}
+
+/// `url`'s origin if it is HTTPS and self-signed certificates are allowed for
+/// it, by `insecure` or the environment.
+fn insecure_origin(url: &str, insecure: bool) -> Option<String> {
+    if !insecure && !env_enabled() {
+        return None;
+    }
+    let url = url.parse::<Url>().ok().filter(|u| u.scheme() == "https")?;
+    Some(url.origin().ascii_serialization())
+}
+
+/// Whether a remote at `url` with the given flag would skip verification,
+/// before it is made the current one.
+pub fn allowed(url: &str, insecure: bool) -> bool {
+    insecure_origin(url, insecure).is_some()
+}
+
+/// Whether requests to `url` skip certificate verification.
+pub fn skips_verification(url: &str) -> bool {
+    let Some(insecure) = INSECURE_ORIGIN.lock().unwrap().clone() else { return false };
+    url.parse::<Url>().is_ok_and(|u| u.origin().ascii_serialization() == insecure)
+}
+
+/// An HTTP client that accepts any certificate, through `proxy` if given.
+pub fn insecure_agent(proxy: Option<&Url>) -> ureq::Agent {
+    static CONFIG: OnceLock<Arc<rustls::ClientConfig>> = OnceLock::new();
+    let config = CONFIG.get_or_init(|| {
+        let provider = Arc::new(crypto::ring::default_provider());
+        let config = rustls::ClientConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .expect("ring supports the default TLS versions")
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(AcceptAny(provider)))
+            .with_no_client_auth();
+        Arc::new(config)
+    });
+    let mut builder = ureq::AgentBuilder::new().tls_config(config.clone());
+    if let Some(proxy) = proxy.and_then(|p| ureq::Proxy::new(p.as_str()).ok()) {
+        builder = builder.proxy(proxy);
+    }
+    builder.build()
+}
+
+/// Skips the certificate chain and name checks but still checks the
+/// handshake signatures, so the connection is at least encrypted.
+#[derive(Debug)]
+struct AcceptAny(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAny {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+fn webview_insecure() -> bool {
+    WEBVIEW_INSECURE.get().copied().unwrap_or(false)
+}
+
+/// Lets WebView2 load the remote page despite its certificate. The flag is
+/// browser-wide, and every window must pass the same arguments or WebView2
+/// refuses to create it, so all window builders go through here.
+pub fn prepare<'a>(
+    builder: WebviewWindowBuilder<'a, tauri::Wry, tauri::AppHandle>,
+) -> WebviewWindowBuilder<'a, tauri::Wry, tauri::AppHandle> {
+    #[cfg(windows)]
+    if webview_insecure() {
+        // Replaces wry's defaults, so they are repeated here.
+        return builder.additional_browser_args(
+            "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection --ignore-certificate-errors",
+        );
+    }
+    builder
+}
+
+/// Lets WebKitGTK load the remote page despite its certificate; macOS's
+/// WKWebView has no such switch, so there the certificate has to be trusted
+/// in Keychain Access.
+pub fn apply(window: &WebviewWindow) {
+    if !webview_insecure() {
+        return;
+    }
+    #[cfg(target_os = "linux")]
+    {
+        let result = window.with_webview(|webview| {
+            use webkit2gtk::{WebContextExt, WebViewExt};
+            if let Some(context) = webview.inner().context() {
+                context.set_tls_errors_policy(webkit2gtk::TLSErrorsPolicy::Ignore);
+            }
+        });
+        if let Err(e) = result {
+            log::warn!("failed to relax certificate checks in window {}: {}", window.label(), e);
+        }
+    }
+    #[cfg(target_os = "macos")]
+    log::warn!(
+        "window {} still verifies certificates; trust the backend's certificate in Keychain Access",
+        window.label()
+    );
+    #[cfg(windows)]
+    let _ = window;
+}
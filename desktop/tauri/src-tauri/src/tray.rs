@@ -0,0 +1,263 @@
+//! System tray icon and menu.
+
+use std::{path::Path, thread};
+
+use tauri::{
+    menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
+    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
+    AppHandle, Manager, Wry,
+};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::i18n::{t, tf};
+
+pub const TRAY_ID: &str = "main";
+const PROFILE_PREFIX: &str = "profile:";
+const RECENT_DIR_PREFIX: &str = "recent-dir:";
+const LOG_LEVEL_PREFIX: &str = "log-level:";
+
+/// One checkable item per saved profile, the active one checked.
+fn profiles_menu(app: &AppHandle) -> tauri::Result<Submenu<Wry>> {
+    let saved = crate::profiles::load(app);
+    let mut items = Vec::new();
+    for profile in &saved.profiles {
+        let active = saved.active.as_deref() == Some(profile.name.as_str());
+        let id = format!("{}{}", PROFILE_PREFIX, profile.name);
+        items.push(CheckMenuItem::with_id(app, id, &profile.name, true, active, None::<&str>)?);
+    }
+    let submenu = Submenu::with_id(app, "profiles", t("tray.profiles"), true)?;
+    if items.is_empty() {
+        submenu.append(&MenuItem::with_id(app, "no-profiles", t("tray.no_profiles"), false, None::<&str>)?)?;
+    }
+    let refs: Vec<&dyn IsMenuItem<Wry>> = items.iter().map(|item| item as &dyn IsMenuItem<Wry>).collect();
+    submenu.append_items(&refs)?;
+    Ok(submenu)
+}
+
+/// Recently used storage folders, the current one checked.
+fn recent_dirs_menu(app: &AppHandle) -> tauri::Result<Submenu<Wry>> {
+    let current = app.state::<crate::AppState>().storage_dir.lock().unwrap().clone();
+    let mut items = Vec::new();
+    for dir in crate::storage::recent(app) {
+        let id = format!("{}{}", RECENT_DIR_PREFIX, dir.display());
+        let checked = current.as_deref() == Some(dir.as_path());
+        items.push(CheckMenuItem::with_id(app, id, dir.display().to_string(), true, checked, None::<&str>)?);
+    }
+    let submenu = Submenu::with_id(app, "recent-dirs", t("tray.recent_dirs"), true)?;
+    if items.is_empty() {
+        submenu.append(&MenuItem::with_id(app, "no-recent-dirs", t("tray.no_recent_dirs"), false, None::<&str>)?)?;
+    }
+    let refs: Vec<&dyn IsMenuItem<Wry>> = items.iter().map(|item| item as &dyn IsMenuItem<Wry>).collect();
+    submenu.append_items(&refs)?;
+    Ok(submenu)
+}
+
+/// One checkable item per backend log level, the current one checked.
+fn log_level_menu(app: &AppHandle) -> tauri::Result<Submenu<Wry>> {
+    let current = crate::backend_log_level(&app.state::<crate::AppState>());
+    let submenu = Submenu::with_id(app, "log-level", t("tray.log_level"), true)?;
+    for level in crate::LOG_LEVELS {
+        let id = format!("{}{}", LOG_LEVEL_PREFIX, level);
+        submenu.append(&CheckMenuItem::with_id(app, id, level, true, level == current, None::<&str>)?)?;
+    }
+    Ok(submenu)
+}
+
+/// Switches to a recent folder, dropping it from the list if it is gone.
+fn open_recent_dir(app: &AppHandle, dir: &str) {
+    if let Err(e) = crate::storage::validate(dir) {
+        log::warn!("removing recent storage folder {}: {}", dir, e);
+        crate::storage::forget(app, Path::new(dir));
+        refresh(app);
+        crate::show_error(app, &tf("tray.recent_dir_removed", &[&e]));
+        return;
+    }
+    if let Err(e) = crate::set_storage_dir_blocking(app, dir) {
+        log::error!("switching to storage folder {} failed: {}", dir, e);
+        crate::show_error(app, &e);
+    }
+    // Put the checkmark where the click left it only if the switch happened.
+    refresh(app);
+}
+
+fn menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let show = MenuItem::with_id(app, "show", t("tray.show"), true, None::<&str>)?;
+    let restart = MenuItem::with_id(app, "restart-backend", t("tray.restart_backend"), true, None::<&str>)?;
+    let stop = MenuItem::with_id(app, "stop-backend", t("tray.stop_backend"), true, None::<&str>)?;
+    let restart_app = MenuItem::with_id(app, "restart-app", t("tray.restart_app"), true, None::<&str>)?;
+    let on_top = crate::window_state::always_on_top(app, crate::MAIN_WINDOW);
+    let always_on_top = CheckMenuItem::with_id(app, "always-on-top", t("tray.keep_on_top"), true, on_top, None::<&str>)?;
+    let autostart = CheckMenuItem::with_id(
+        app,
+        "autostart",
+        t("tray.autostart"),
+        true,
+        crate::autostart::is_enabled(app),
+        None::<&str>,
+    )?;
+    let use_local = MenuItem::with_id(app, "use-local", t("tray.use_local"), true, None::<&str>)?;
+    let copy_url = MenuItem::with_id(app, "copy-backend-url", t("tray.copy_url"), true, None::<&str>)?;
+    let open_logs = MenuItem::with_id(app, "open-logs", t("tray.open_logs"), true, None::<&str>)?;
+    let check_updates = MenuItem::with_id(app, "check-updates", t("tray.check_updates"), true, None::<&str>)?;
+    let diagnostics = MenuItem::with_id(app, "copy-diagnostics", t("tray.copy_diagnostics"), true, None::<&str>)?;
+    let about = MenuItem::with_id(app, "about", t("tray.about"), true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "quit", t("tray.quit"), true, None::<&str>)?;
+    Menu::with_items(
+        app,
+        &[
+            &show,
+            &always_on_top,
+            &restart,
+            &stop,
+            &log_level_menu(app)?,
+            &profiles_menu(app)?,
+            &recent_dirs_menu(app)?,
+            &use_local,
+            &copy_url,
+            &open_logs,
+            &check_updates,
+            &autostart,
+            &diagnostics,
+            &about,
+            &PredefinedMenuItem::separator(app)?,
+            &restart_app,
+            &quit,
+        ],
+    )
+}
+
+/// Rebuilds the menu, e.g. after the saved profiles changed.
+pub fn refresh(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else { return };
+    match menu(app) {
+        Ok(menu) => {
+            if let Err(e) = tray.set_menu(Some(menu)) {
+                log::warn!("failed to update the tray menu: {}", e);
+            }
+        }
+        Err(e) => log::warn!("failed to build the tray menu: {}", e),
+    }
+}
+
+pub fn build(app: &AppHandle) -> tauri::Result<()> {
+    let menu = menu(app)?;
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip(t("tray.tooltip"))
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| match event.id.as_ref() {
+            "show" => crate::show_main_window(app),
+            "always-on-top" => {
+                if let Some(window) = app.get_webview_window(crate::MAIN_WINDOW) {
+                    let enabled = !crate::window_state::always_on_top(app, crate::MAIN_WINDOW);
+                    if let Err(e) = crate::apply_always_on_top(&window, enabled) {
+                        log::warn!("failed to change always-on-top: {}", e);
+                    }
+                }
+            }
+            "autostart" => {
+                if let Err(e) = crate::autostart::set(app, !crate::autostart::is_enabled(app)) {
+                    log::error!("{}", e);
+                    let app = app.clone();
+                    thread::spawn(move || crate::show_error(&app, &e));
+                }
+                refresh(app);
+            }
+            "restart-backend" => {
+                let app = app.clone();
+                thread::spawn(move || {
+                    if let Err(e) = crate::restart_backend_blocking(&app) {
+                        log::error!("backend restart failed: {}", e);
+                        crate::show_error(&app, &e.to_string());
+                    }
+                });
+            }
+            "stop-backend" => {
+                let app = app.clone();
+                thread::spawn(move || {
+                    crate::stop_backend_confirmed(&app, false);
+                });
+            }
+            "use-local" => {
+                let app = app.clone();
+                thread::spawn(move || {
+                    if let Err(e) = crate::use_local_backend_blocking(&app) {
+                        log::error!("switching to a local backend failed: {}", e);
+                        crate::show_error(&app, &e);
+                    }
+                });
+            }
+            "open-logs" => {
+                let app = app.clone();
+                thread::spawn(move || {
+                    if let Err(e) = crate::open_logs_dir(app.clone()) {
+                        crate::show_error(&app, &e);
+                    }
+                });
+            }
+            "copy-backend-url" => {
+                if let Err(e) = crate::copy_backend_url(app.clone()) {
+                    crate::show_error(app, &e);
+                }
+            }
+            "check-updates" => crate::updater::check_in_background(app, true),
+            "copy-diagnostics" => {
+                let app = app.clone();
+                thread::spawn(move || {
+                    let report = crate::diagnostics::collect(&app);
+                    if let Err(e) = app.clipboard().write_text(report) {
+                        crate::show_error(&app, &tf("tray.copy_diagnostics_failed", &[&e]));
+                    }
+                });
+            }
+            "about" => {
+                let app = app.clone();
+                thread::spawn(move || crate::about::show(&app));
+            }
+            "restart-app" => {
+                let app = app.clone();
+                thread::spawn(move || crate::restart_app_blocking(&app));
+            }
+            "quit" => crate::request_quit(app),
+            id => {
+                if let Some(level) = id.strip_prefix(LOG_LEVEL_PREFIX) {
+                    let app = app.clone();
+                    let level = level.to_string();
+                    thread::spawn(move || {
+                        if let Err(e) = crate::set_backend_log_level_blocking(&app, &level) {
+                            log::error!("restarting at log level {} failed: {}", level, e);
+                            crate::show_error(&app, &e);
+                        }
+                    });
+                    return;
+                }
+                if let Some(dir) = id.strip_prefix(RECENT_DIR_PREFIX) {
+                    let app = app.clone();
+                    let dir = dir.to_string();
+                    thread::spawn(move || open_recent_dir(&app, &dir));
+                    return;
+                }
+                let Some(name) = id.strip_prefix(PROFILE_PREFIX) else { return };
+                let app = app.clone();
+                let name = name.to_string();
+                thread::spawn(move || {
+                    if let Err(e) = crate::activate_profile_blocking(&app, &name) {
+                        log::error!("switching to profile {} failed: {}", name, e);
+                        crate::show_error(&app, &e);
+                        // Undo the checkmark the click toggled.
+                        refresh(&app);
+                    }
+                });
+            }
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. } = event {
+                crate::show_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
@@ -0,0 +1,79 @@
+//! In-app updates through tauri-plugin-updater.
+
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+};
+
+use tauri::AppHandle;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tauri_plugin_updater::UpdaterExt;
+
+use crate::i18n::{t, tf};
+
+// At most one check (and its dialogs) at a time.
+static CHECKING: AtomicBool = AtomicBool::new(false);
+
+/// Checks for an update on a background thread. `interactive` checks (from the
+/// tray or menu) also report "up to date" and errors; the startup check only
+/// logs them. Does nothing while another check is still running.
+pub fn check_in_background(app: &AppHandle, interactive: bool) {
+    if CHECKING.swap(true, Ordering::SeqCst) {
+        log::info!("update check already running");
+        return;
+    }
+    let app = app.clone();
+    thread::spawn(move || {
+        if let Err(e) = tauri::async_runtime::block_on(check(&app, interactive)) {
+            // Offline is the common case here; never bother the user about it unasked.
+            log::warn!("update check failed: {}", e);
+            if interactive {
+                let reason = match e {
+                    tauri_plugin_updater::Error::Reqwest(_) => t("update.offline"),
+                    _ => t("update.failed"),
+                };
+                crate::show_error(&app, &format!("{}\n\n{}", reason, e));
+            }
+        }
+        CHECKING.store(false, Ordering::SeqCst);
+    });
+}
+
+fn info(app: &AppHandle, message: &str) {
+    app.dialog().message(message).title(t("dialog.title")).kind(MessageDialogKind::Info).blocking_show();
+}
+
+async fn check(app: &AppHandle, interactive: bool) -> tauri_plugin_updater::Result<()> {
+    // On Windows install() hands over to the installer and exits this process
+    // without running our exit path; stop the backend just before that.
+    let exiting = app.clone();
+    let mut updater = app.updater_builder().on_before_exit(move || crate::shutdown(&exiting));
+    if let Some(proxy) = crate::proxy::for_updates() {
+        updater = updater.proxy(proxy.clone());
+    }
+    let Some(update) = updater.build()?.check().await? else {
+        log::info!("no update available");
+        if interactive {
+            info(app, &tf("update.latest", &[&app.package_info().version]));
+        }
+        return Ok(());
+    };
+    log::info!("update available: {} -> {}", update.current_version, update.version);
+    let install = app
+        .dialog()
+        .message(tf("update.available", &[&update.version, &update.current_version]))
+        .title(t("update.title"))
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::OkCancelCustom(t("update.install").into(), t("update.later").into()))
+        .blocking_show();
+    if !install {
+        return Ok(());
+    }
+    // download() verifies the signature against the configured public key.
+    let bytes = update.download(|_, _| {}, || {}).await?;
+    // Install while the backend still runs, so a failed install leaves a working app.
+    update.install(bytes)?;
+    log::info!("update {} installed, restarting", update.version);
+    crate::shutdown(app);
+    app.restart();
+}
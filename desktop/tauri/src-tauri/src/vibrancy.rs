@@ -0,0 +1,53 @@
+//! Optional macOS vibrancy for the main window (`vibrancy = true` in
+//! runicorn.toml): a transparent titlebar and a translucent background the
+//! page's outer layout shows through. A no-op on other platforms.
+
+use tauri::{AppHandle, Manager, WebviewWindow, WebviewWindowBuilder, Wry};
+
+use crate::AppState;
+
+// Clears only the page's outermost backgrounds; cards and tables keep theirs.
+#[cfg(target_os = "macos")]
+const TRANSPARENT_PAGE: &str = r#"document.addEventListener("DOMContentLoaded", () => {
+  const style = document.createElement("style");
+  style.textContent = "html, body, #root, #root > .ant-layout { background: transparent !important; }";
+  document.head.appendChild(style);
+});"#;
+
+fn enabled(app: &AppHandle) -> bool {
+    cfg!(target_os = "macos") && app.state::<AppState>().config.vibrancy()
+}
+
+/// Makes the window and page transparent so the effect can show through.
+pub fn prepare<'a>(
+    app: &AppHandle,
+    builder: WebviewWindowBuilder<'a, Wry, AppHandle>,
+) -> WebviewWindowBuilder<'a, Wry, AppHandle> {
+    if !enabled(app) {
+        return builder;
+    }
+    #[cfg(target_os = "macos")]
+    return builder
+        .transparent(true)
+        .title_bar_style(tauri::TitleBarStyle::Transparent)
+        .initialization_script(TRANSPARENT_PAGE);
+    #[cfg(not(target_os = "macos"))]
+    builder
+}
+
+/// Puts the vibrancy effect behind a window made with [`prepare`].
+pub fn apply(app: &AppHandle, window: &WebviewWindow) {
+    if !enabled(app) {
+        return;
+    }
+    #[cfg(target_os = "macos")]
+    {
+        use window_vibrancy::{apply_vibrancy, NSVisualEffectMaterial, NSVisualEffectState};
+        let applied = apply_vibrancy(window, NSVisualEffectMaterial::Sidebar, Some(NSVisualEffectState::FollowsWindowActiveState), None);
+        if let Err(e) = applied {
+            log::warn!("failed to apply vibrancy: {}", e);
+        }
+    }
+    #[cfg(not(target_os = "macos"))]
+    let _ = window;
+}
@@ -0,0 +1,107 @@
+//! Watches a backend we spawned and restarts it when it dies or stops
+//! answering health checks after startup.
+
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+// A live process must fail this many checks in a row before it counts as hung.
+const MAX_FAILED_CHECKS: u32 = 3;
+// More restarts than this within RESTART_WINDOW means something is persistently wrong.
+const MAX_RESTARTS: usize = 3;
+const RESTART_WINDOW: Duration = Duration::from_secs(60);
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+enum Check {
+    /// No backend of ours to watch (attached, stopped, or mid-restart).
+    Idle,
+    Healthy,
+    Exited,
+    Unhealthy,
+}
+
+fn check(state: &AppState) -> Check {
+    if state.shutting_down.load(Ordering::SeqCst) {
+        return Check::Idle;
+    }
+    // A boot or restart in progress owns the child; don't judge it mid-flight.
+    let Ok(_boot) = state.boot_lock.try_lock() else { return Check::Idle };
+    match state.child.lock().unwrap().as_mut().map(|child| child.has_exited()) {
+        None => return Check::Idle,
+        Some(true) => return Check::Exited,
+        Some(false) => {}
+    }
+    let Some(url) = state.backend_url.lock().unwrap().clone() else { return Check::Idle };
+    // Same marker as startup, so a foreign program that took the port after a crash isn't "healthy".
+    let reply = crate::auth::get(&crate::health_url_for(&url)).timeout(crate::PROBE_TIMEOUT).call();
+    if reply.is_ok_and(crate::is_runicorn_health) {
+        Check::Healthy
+    } else {
+        Check::Unhealthy
+    }
+}
+
+/// Starts the watchdog thread; later calls are no-ops.
+pub fn spawn(app: &AppHandle) {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    thread::spawn(move || run(app));
+}
+
+fn run(app: AppHandle) {
+    let mut failed_checks = 0;
+    let mut restarts: VecDeque<Instant> = VecDeque::new();
+    loop {
+        thread::sleep(CHECK_INTERVAL);
+        let state: tauri::State<AppState> = app.state();
+        let reason = match check(&state) {
+            Check::Idle | Check::Healthy => {
+                failed_checks = 0;
+                continue;
+            }
+            Check::Exited => crate::i18n::t("watchdog.exited").to_string(),
+            Check::Unhealthy => {
+                failed_checks += 1;
+                if failed_checks < MAX_FAILED_CHECKS {
+                    continue;
+                }
+                crate::i18n::tf("watchdog.unhealthy", &[&failed_checks])
+            }
+        };
+        failed_checks = 0;
+        log::error!("{}", reason);
+        crate::emit_event(&app, crate::EVENT_CRASHED, json!({ "reason": reason }));
+        crate::set_backend_state(&app, crate::BackendState::Failed(reason.clone()));
+
+        let now = Instant::now();
+        while restarts.front().is_some_and(|t| now.duration_since(*t) > RESTART_WINDOW) {
+            restarts.pop_front();
+        }
+        if restarts.len() >= MAX_RESTARTS {
+            log::error!("backend crashed {} times within {:?}, not restarting again", restarts.len() + 1, RESTART_WINDOW);
+            // Drop the dead child so the watchdog goes idle until the user restarts by hand.
+            crate::kill_child(&state);
+            restarts.clear();
+            crate::show_error(&app, &crate::i18n::tf("watchdog.gave_up", &[&reason]));
+            continue;
+        }
+        restarts.push_back(now);
+        crate::emit_event(&app, crate::EVENT_RESTARTING, json!({ "attempt": restarts.len(), "reason": reason }));
+        if let Err(e) = crate::restart_backend_blocking(&app) {
+            log::error!("automatic backend restart failed: {}", e);
+            crate::show_error(&app, &crate::i18n::tf("watchdog.restart_failed", &[&e]));
+        }
+    }
+}
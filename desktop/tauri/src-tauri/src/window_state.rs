@@ -0,0 +1,195 @@
+//! Persists window geometry (plus maximized/fullscreen, zoom and the
+//! always-on-top choice) to
+//! `<app config dir>/window-state.json` so windows reopen where the user left them.
+
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, LogicalPosition, LogicalSize, Manager, WebviewWindow, WebviewWindowBuilder, Window, Wry};
+
+const STATE_FILE_NAME: &str = "window-state.json";
+// Below this the UI is unusable; treat smaller saved sizes as corrupt.
+const MIN_WIDTH: f64 = 400.0;
+const MIN_HEIGHT: f64 = 300.0;
+// How much of the title bar must land on a monitor for a saved position to be kept.
+const VISIBLE_MARGIN: f64 = 100.0;
+
+/// Geometry of one window in logical pixels.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowState {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    #[serde(default)]
+    pub maximized: bool,
+    #[serde(default)]
+    pub fullscreen: bool,
+    #[serde(default)]
+    pub always_on_top: bool,
+    #[serde(default = "default_zoom")]
+    pub zoom: f64,
+}
+
+fn default_zoom() -> f64 {
+    1.0
+}
+
+fn state_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(STATE_FILE_NAME))
+}
+
+fn load_all(app: &AppHandle) -> BTreeMap<String, WindowState> {
+    state_path(app)
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_all(app: &AppHandle, states: &BTreeMap<String, WindowState>) {
+    let Some(path) = state_path(app) else { return };
+    let result = path
+        .parent()
+        .map(fs::create_dir_all)
+        .unwrap_or(Ok(()))
+        .and_then(|_| fs::write(&path, serde_json::to_string_pretty(states).unwrap_or_default()));
+    if let Err(e) = result {
+        log::warn!("failed to save window state to {}: {}", path.display(), e);
+    }
+}
+
+/// True when the top edge of the window overlaps a connected monitor.
+fn is_visible(app: &AppHandle, s: &WindowState) -> bool {
+    let Ok(monitors) = app.available_monitors() else { return true };
+    if monitors.is_empty() {
+        return true;
+    }
+    monitors.iter().any(|m| {
+        let scale = m.scale_factor();
+        let pos = m.position().to_logical::<f64>(scale);
+        let size = m.size().to_logical::<f64>(scale);
+        s.x + s.width - VISIBLE_MARGIN >= pos.x
+            && s.x + VISIBLE_MARGIN <= pos.x + size.width
+            && s.y >= pos.y - VISIBLE_MARGIN / 2.0
+            && s.y + VISIBLE_MARGIN / 2.0 <= pos.y + size.height
+    })
+}
+
+/// Applies the saved geometry for `label`, dropping a position that would
+/// put the window off-screen (e.g. after unplugging a monitor). The position
+/// also picks the monitor a maximized or fullscreen window fills, so the check
+/// applies to those too.
+pub fn apply<'a>(
+    app: &AppHandle,
+    label: &str,
+    builder: WebviewWindowBuilder<'a, Wry, AppHandle>,
+) -> WebviewWindowBuilder<'a, Wry, AppHandle> {
+    let Some(s) = load_all(app).remove(label) else { return builder };
+    let mut builder = builder.inner_size(s.width.max(MIN_WIDTH), s.height.max(MIN_HEIGHT));
+    if is_visible(app, &s) {
+        builder = builder.position(s.x, s.y);
+    } else {
+        log::info!("saved position of window '{}' is off-screen, centering it", label);
+        builder = builder.center();
+    }
+    builder.maximized(s.maximized).fullscreen(s.fullscreen).always_on_top(s.always_on_top)
+}
+
+/// The saved geometry of `label`, if any.
+pub fn saved(app: &AppHandle, label: &str) -> Option<WindowState> {
+    load_all(app).remove(label)
+}
+
+/// Moves an open window to `s` (e.g. another workspace's layout) and keeps it
+/// as the window's saved state.
+pub fn restore(window: &WebviewWindow, s: &WindowState) {
+    let app = window.app_handle();
+    let visible = is_visible(app, s);
+    let result = (|| -> tauri::Result<()> {
+        window.set_fullscreen(false)?;
+        window.unmaximize()?;
+        window.set_size(LogicalSize::new(s.width.max(MIN_WIDTH), s.height.max(MIN_HEIGHT)))?;
+        if visible {
+            window.set_position(LogicalPosition::new(s.x, s.y))?;
+        }
+        if s.maximized {
+            window.maximize()?;
+        }
+        window.set_fullscreen(s.fullscreen)?;
+        window.set_always_on_top(s.always_on_top)?;
+        window.set_zoom(s.zoom)
+    })();
+    if let Err(e) = result {
+        log::warn!("failed to restore the layout of window '{}': {}", window.label(), e);
+    }
+    let mut states = load_all(app);
+    states.insert(window.label().to_string(), s.clone());
+    save_all(app, &states);
+}
+
+/// The saved always-on-top choice for `label`.
+pub fn always_on_top(app: &AppHandle, label: &str) -> bool {
+    load_all(app).get(label).is_some_and(|s| s.always_on_top)
+}
+
+/// The saved zoom factor for `label`, 1.0 if none.
+pub fn zoom(app: &AppHandle, label: &str) -> f64 {
+    load_all(app).get(label).map_or(1.0, |s| s.zoom)
+}
+
+/// Saves the current geometry, then lets `change` adjust the stored entry.
+fn update(window: &Window, change: impl FnOnce(&mut WindowState)) {
+    save(window);
+    let app = window.app_handle();
+    let mut states = load_all(app);
+    if let Some(s) = states.get_mut(window.label()) {
+        change(s);
+        save_all(app, &states);
+    }
+}
+
+/// Remembers the always-on-top choice along with the current geometry.
+pub fn save_always_on_top(window: &Window, enabled: bool) {
+    update(window, |s| s.always_on_top = enabled);
+}
+
+/// Remembers the zoom factor along with the current geometry.
+pub fn save_zoom(window: &Window, factor: f64) {
+    update(window, |s| s.zoom = factor);
+}
+
+/// Records the window's current geometry. While maximized or fullscreen only
+/// the flags are updated, so leaving that state after a restart returns to the
+/// previous size; without a previous size the current one is kept.
+pub fn save(window: &Window) {
+    let app = window.app_handle();
+    let mut states = load_all(app);
+    let label = window.label().to_string();
+    let maximized = window.is_maximized().unwrap_or(false);
+    let fullscreen = window.is_fullscreen().unwrap_or(false);
+    if let Some(s) = states.get_mut(&label).filter(|_| maximized || fullscreen) {
+        s.maximized = maximized;
+        s.fullscreen = fullscreen;
+    } else {
+        let (Ok(scale), Ok(pos), Ok(size)) = (window.scale_factor(), window.outer_position(), window.inner_size()) else {
+            return;
+        };
+        let pos = pos.to_logical::<f64>(scale);
+        let size = size.to_logical::<f64>(scale);
+        let prev = states.get(&label);
+        let always_on_top = prev.is_some_and(|s| s.always_on_top);
+        let zoom = prev.map_or(1.0, |s| s.zoom);
+        let state = WindowState {
+            x: pos.x,
+            y: pos.y,
+            width: size.width,
+            height: size.height,
+            maximized,
+            fullscreen,
+            always_on_top,
+            zoom,
+        };
+        states.insert(label, state);
+    }
+    save_all(app, &states);
+}
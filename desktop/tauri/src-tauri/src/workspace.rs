@@ -0,0 +1,122 @@
+//! Per-project setup of the main window: its geometry and zoom, a theme
+//! override and the last page shown, kept for each storage folder in
+//! `<app config dir>/workspaces.json` and restored when switching to that
+//! folder.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Theme};
+
+use crate::window_state::{self, WindowState};
+
+const WORKSPACES_FILE_NAME: &str = "workspaces.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Workspace {
+    #[serde(default)]
+    window: Option<WindowState>,
+    /// `light` or `dark`; `None` follows the OS.
+    #[serde(default)]
+    theme: Option<String>,
+    /// Path of the page the main window was on, e.g. `/runs/<id>`.
+    #[serde(default)]
+    route: Option<String>,
+}
+
+fn file_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(WORKSPACES_FILE_NAME))
+}
+
+fn key(dir: &Path) -> String {
+    dir.to_string_lossy().into_owned()
+}
+
+fn load_all(app: &AppHandle) -> BTreeMap<String, Workspace> {
+    let Some(path) = file_path(app) else { return BTreeMap::new() };
+    let Ok(text) = fs::read_to_string(&path) else { return BTreeMap::new() };
+    serde_json::from_str(&text).unwrap_or_else(|e| {
+        log::warn!("ignoring malformed {}: {}", path.display(), e);
+        BTreeMap::new()
+    })
+}
+
+fn save_all(app: &AppHandle, workspaces: &BTreeMap<String, Workspace>) {
+    let Some(path) = file_path(app) else { return };
+    let result = path
+        .parent()
+        .map(fs::create_dir_all)
+        .unwrap_or(Ok(()))
+        .and_then(|_| fs::write(&path, serde_json::to_string_pretty(workspaces).unwrap_or_default()));
+    if let Err(e) = result {
+        log::warn!("failed to save workspaces to {}: {}", path.display(), e);
+    }
+}
+
+fn update(app: &AppHandle, dir: &Path, change: impl FnOnce(&mut Workspace)) {
+    let mut workspaces = load_all(app);
+    change(workspaces.entry(key(dir)).or_default());
+    save_all(app, &workspaces);
+}
+
+pub fn parse_theme(raw: &str) -> Option<Theme> {
+    match raw {
+        "light" => Some(Theme::Light),
+        "dark" => Some(Theme::Dark),
+        _ => None,
+    }
+}
+
+/// The theme override saved for `dir`, if any.
+pub fn theme(app: &AppHandle, dir: Option<&Path>) -> Option<Theme> {
+    let dir = dir?;
+    load_all(app).remove(&key(dir))?.theme.as_deref().and_then(parse_theme)
+}
+
+/// Remembers `theme` (`None` to follow the OS) for `dir`.
+pub fn save_theme(app: &AppHandle, dir: &Path, theme: Option<&str>) {
+    update(app, dir, |ws| ws.theme = theme.map(str::to_string));
+}
+
+/// Records the main window's geometry, zoom and page for `dir`.
+pub fn capture(app: &AppHandle, dir: &Path) {
+    let Some(window) = app.get_webview_window(crate::MAIN_WINDOW) else { return };
+    let webview: &tauri::Webview = window.as_ref();
+    window_state::save(&webview.window());
+    let geometry = window_state::saved(app, crate::MAIN_WINDOW);
+    // Error and "stopped" pages are data: URLs, not somewhere to come back to.
+    let route = window.url().ok().filter(|url| url.scheme() != "data").map(|url| url.path().to_string());
+    update(app, dir, |ws| {
+        ws.window = geometry.or(ws.window.take());
+        if route.is_some() {
+            ws.route = route;
+        }
+    });
+}
+
+/// Puts the main window in `dir`'s saved layout and theme and sends it back
+/// to `dir`'s last page on the backend at `backend`. A folder without a
+/// workspace keeps the current layout and follows the OS theme.
+pub fn restore(app: &AppHandle, dir: &Path, backend: &str) {
+    let Some(window) = app.get_webview_window(crate::MAIN_WINDOW) else { return };
+    let ws = load_all(app).remove(&key(dir)).unwrap_or_default();
+    if let Some(geometry) = &ws.window {
+        window_state::restore(&window, geometry);
+    }
+    if let Err(e) = window.set_theme(ws.theme.as_deref().and_then(parse_theme)) {
+        log::warn!("failed to set the theme: {}", e);
+    }
+    let Some(route) = ws.route.as_deref().filter(|r| *r != "/") else { return };
+    let target = crate::window_url(app, backend, None).map(|mut url| {
+        url.set_path(route);
+        url
+    });
+    match target.map(|url| window.navigate(url).map_err(|e| e.to_string())) {
+        Ok(Ok(())) => log::info!("restored workspace page {} for {}", route, dir.display()),
+        Ok(Err(e)) | Err(e) => log::warn!("failed to open {} for {}: {}", route, dir.display(), e),
+    }
+}
@@ -8,6 +8,9 @@ from __future__ import annotations
 
 import asyncio
 import logging
+import os
+import signal
+import sys
 from pathlib import Path
 from typing import Optional
 
@@ -17,6 +20,8 @@ from fastapi.staticfiles import StaticFiles
 
 from .utils.logging import setup_logging
 from .middleware.rate_limit import RateLimitMiddleware
+from .middleware.auth_token import AuthTokenMiddleware
+from .middleware.csp import CSPMiddleware
 from .services.storage import get_storage_root, periodic_status_check
 from .api import (
     health_router,
@@ -40,6 +45,25 @@ from .. import __version__
 logger = logging.getLogger(__name__)
 
 
+def install_sigbreak_handler() -> None:
+    """
+    Treat Ctrl+Break like Ctrl+C on Windows.
+
+    The desktop app stops a windowless backend with Ctrl+Break, since it has no
+    window to receive WM_CLOSE. uvicorn before 0.29 leaves SIGBREAK at its
+    default, which ends the process at once; forwarding it as SIGINT gives the
+    same graceful shutdown as Ctrl+C. Newer uvicorn installs its own handler
+    over this one when the server starts.
+    """
+    if sys.platform != "win32":
+        return
+
+    def _forward(signum, frame):  # noqa: ARG001
+        signal.raise_signal(signal.SIGINT)
+
+    signal.signal(signal.SIGBREAK, _forward)
+
+
 def create_app(storage: Optional[str] = None) -> FastAPI:
     """
     Create and configure the FastAPI application.
@@ -55,6 +79,7 @@ def create_app(storage: Optional[str] = None) -> FastAPI:
     
     # Setup logging
     setup_logging()
+    install_sigbreak_handler()
     
     # Create FastAPI app
     app = FastAPI(
@@ -75,6 +100,16 @@ def create_app(storage: Optional[str] = None) -> FastAPI:
     # Add rate limiting middleware
     app.add_middleware(RateLimitMiddleware)
     
+    # Content-Security-Policy requested by the desktop app
+    csp = os.environ.get("RUNICORN_CSP")
+    if csp:
+        app.add_middleware(CSPMiddleware, policy=csp)
+    
+    # Require the desktop app's token when it launched us (outermost middleware)
+    auth_token = os.environ.get("RUNICORN_AUTH_TOKEN")
+    if auth_token:
+        app.add_middleware(AuthTokenMiddleware, token=auth_token)
+    
     # Background task for status checking
     _status_check_task = None
     
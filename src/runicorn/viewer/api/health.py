@@ -14,7 +14,7 @@ from ..utils.incremental_cache import get_incremental_metrics_cache
 router = APIRouter()
 
 
-@router.get("/health")
+@router.api_route("/health", methods=["GET", "HEAD"])
 async def health(request: Request) -> Dict[str, Any]:
     """
     Get system health status.
@@ -30,6 +30,7 @@ async def health(request: Request) -> Dict[str, Any]:
     from .. import __version__
     
     return {
+        "service": "runicorn",
         "status": "ok", 
         "storage": str(storage_root),
         "version": __version__,
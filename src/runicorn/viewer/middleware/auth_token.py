@@ -0,0 +1,84 @@
+"""
+Loopback Auth Token Middleware
+
+The desktop app starts the viewer with ``RUNICORN_AUTH_TOKEN`` set. Every
+request must then carry that token, so other local processes and browser tabs
+cannot read experiment data from the loopback port.
+"""
+from __future__ import annotations
+
+import hmac
+from http.cookies import CookieError, SimpleCookie
+from typing import Optional
+from urllib.parse import parse_qs
+
+from starlette.responses import JSONResponse
+from starlette.types import ASGIApp, Message, Receive, Scope, Send
+
+TOKEN_HEADER = "x-runicorn-token"
+TOKEN_COOKIE = "runicorn_token"
+TOKEN_QUERY = "runicorn_token"
+
+
+class AuthTokenMiddleware:
+    """
+    Reject HTTP and WebSocket requests that don't present the shared token.
+
+    The token is accepted from the ``X-Runicorn-Token`` header, the
+    ``runicorn_token`` cookie, or a ``runicorn_token`` query parameter. A
+    request authenticated by query parameter also gets the cookie set, so the
+    desktop window only needs the token in the first URL it loads.
+    """
+
+    def __init__(self, app: ASGIApp, token: str) -> None:
+        self.app = app
+        self.token = token
+
+    def _matches(self, candidate: Optional[str]) -> bool:
+        return bool(candidate) and hmac.compare_digest(candidate.encode(), self.token.encode())
+
+    async def __call__(self, scope: Scope, receive: Receive, send: Send) -> None:
+        if scope["type"] not in ("http", "websocket"):
+            await self.app(scope, receive, send)
+            return
+
+        # CORS preflight carries no credentials and returns no data
+        if scope["type"] == "http" and scope["method"] == "OPTIONS":
+            await self.app(scope, receive, send)
+            return
+
+        headers = {k.decode("latin-1").lower(): v.decode("latin-1") for k, v in scope.get("headers", [])}
+        query = parse_qs(scope.get("query_string", b"").decode("latin-1"))
+        from_query = self._matches((query.get(TOKEN_QUERY) or [None])[0])
+
+        cookies = SimpleCookie()
+        try:
+            cookies.load(headers.get("cookie", ""))
+        except CookieError:
+            pass
+        from_cookie = TOKEN_COOKIE in cookies and self._matches(cookies[TOKEN_COOKIE].value)
+
+        if not (from_query or from_cookie or self._matches(headers.get(TOKEN_HEADER))):
+            if scope["type"] == "websocket":
+                await send({"type": "websocket.close", "code": 4401})
+                return
+            # ``service`` lets another desktop instance tell this apart from a foreign program
+            response = JSONResponse(
+                {"detail": "Missing or invalid Runicorn auth token", "service": "runicorn"}, status_code=401
+            )
+            await response(scope, receive, send)
+            return
+
+        if from_query and scope["type"] == "http":
+            send = self._setting_cookie(send)
+        await self.app(scope, receive, send)
+
+    def _setting_cookie(self, send: Send) -> Send:
+        cookie = f"{TOKEN_COOKIE}={self.token}; Path=/; HttpOnly; SameSite=Strict".encode("latin-1")
+
+        async def wrapped(message: Message) -> None:
+            if message["type"] == "http.response.start":
+                message = {**message, "headers": [*message.get("headers", []), (b"set-cookie", cookie)]}
+            await send(message)
+
+        return wrapped
@@ -0,0 +1,29 @@
+"""
+Content-Security-Policy Middleware
+
+Adds the policy from ``RUNICORN_CSP`` to every HTTP response. The desktop app
+sets it so its webview only loads and connects to what the viewer serves.
+"""
+from __future__ import annotations
+
+from starlette.types import ASGIApp, Message, Receive, Scope, Send
+
+
+class CSPMiddleware:
+    """Attach a fixed Content-Security-Policy header to HTTP responses."""
+
+    def __init__(self, app: ASGIApp, policy: str) -> None:
+        self.app = app
+        self.header = (b"content-security-policy", policy.encode("latin-1"))
+
+    async def __call__(self, scope: Scope, receive: Receive, send: Send) -> None:
+        if scope["type"] != "http":
+            await self.app(scope, receive, send)
+            return
+
+        async def wrapped(message: Message) -> None:
+            if message["type"] == "http.response.start":
+                message = {**message, "headers": [*message.get("headers", []), self.header]}
+            await send(message)
+
+        await self.app(scope, receive, wrapped)
@@ -0,0 +1,52 @@
+from __future__ import annotations
+
+import subprocess
+import sys
+import time
+
+import pytest
+
+# The desktop app stops its backend with Ctrl+Break on Windows; this checks that
+# the viewer turns it into the SIGINT path uvicorn shuts down gracefully on.
+CHILD = """
+import signal, sys, time
+from runicorn.viewer import install_sigbreak_handler
+
+def on_sigint(signum, frame):
+    print("graceful", flush=True)
+    sys.exit(0)
+
+signal.signal(signal.SIGINT, on_sigint)
+install_sigbreak_handler()
+print("ready", flush=True)
+time.sleep(30)
+sys.exit(1)
+"""
+
+
+@pytest.mark.skipif(sys.platform != "win32", reason="Ctrl+Break only exists on Windows")
+def test_ctrl_break_runs_the_sigint_handler() -> None:
+    import signal
+
+    child = subprocess.Popen(
+        [sys.executable, "-c", CHILD],
+        stdout=subprocess.PIPE,
+        text=True,
+        creationflags=subprocess.CREATE_NEW_PROCESS_GROUP,
+    )
+    try:
+        assert child.stdout is not None
+        assert child.stdout.readline().strip() == "ready"
+        time.sleep(0.2)
+        child.send_signal(signal.CTRL_BREAK_EVENT)
+        out, _ = child.communicate(timeout=10)
+    finally:
+        child.kill()
+    assert child.returncode == 0
+    assert "graceful" in out
+
+
+def test_install_sigbreak_handler_is_a_noop_elsewhere() -> None:
+    from runicorn.viewer import install_sigbreak_handler
+
+    install_sigbreak_handler()
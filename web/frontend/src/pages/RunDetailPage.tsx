@@ -299,6 +299,13 @@ export default function RunDetailPage() {
 
   const wsProto = location.protocol === 'https:' ? 'wss' : 'ws'
   const logUrl = useMemo(() => {
+    // Inside the desktop app's embedded UI, the API lives on the local backend
+    const desktop = (window as any).__RUNICORN_BACKEND__
+    if (desktop?.url) {
+      const asWs = String(desktop.url).replace(/^http/i, 'ws').replace(/\/$/, '')
+      const token = desktop.token ? `?runicorn_token=${encodeURIComponent(desktop.token)}` : ''
+      return `${asWs}/api/runs/${id}/logs/ws${token}`
+    }
     const base: string = (import.meta as any).env?.VITE_API_BASE || '/api'
     if (/^https?:/i.test(base)) {
       const asWs = base.replace(/^http/i, wsProto)